@@ -0,0 +1,246 @@
+// Copyright 2025 Stellar Development Foundation and contributors. Licensed
+// under the Apache License, Version 2.0. See the COPYING file at the root
+// of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
+
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::callers::functions_reaching;
+use crate::chains::reachable_functions;
+use crate::parsing::{CallEdge, CallGraphData, RootKind};
+use crate::policy::{unguarded_routes, Guards};
+use crate::vals::symbol_val;
+
+/// Host functions that check authorization
+pub const AUTH_FUNCTIONS: [&str; 2] = ["require_auth", "require_auth_for_args"];
+
+/// Host functions that change contract data or code
+pub const STORAGE_FUNCTIONS: [&str; 3] = ["put_contract_data", "del_contract_data", "update_current_contract_wasm"];
+
+/// Host functions that call another contract
+pub const CROSS_CONTRACT_FUNCTIONS: [&str; 2] = ["call", "try_call"];
+
+/// The argument of `call` and `try_call` naming the contract function to call
+const CALLED_FUNCTION_ARG: usize = 1;
+
+/// The token contract function a transfer calls
+const TRANSFER_FUNCTION: &str = "transfer";
+
+/// Kinds of sensitive host interaction the audit looks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Capability {
+    /// Writing or deleting contract data, or replacing the contract's code
+    Storage,
+    /// A cross-contract call to a `transfer` function, e.g. through the SDK's
+    /// `TokenClient::transfer`
+    Transfer,
+    /// Any call to another contract
+    CrossContract,
+}
+
+impl Capability {
+    pub const ALL: [Capability; 3] = [Capability::Storage, Capability::Transfer, Capability::CrossContract];
+
+    /// Whether reaching this without authorization gets an export flagged.
+    /// Cross-contract calls are often read-only, so they are only reported.
+    pub fn is_mutation(self) -> bool {
+        matches!(self, Capability::Storage | Capability::Transfer)
+    }
+}
+
+impl fmt::Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Capability::Storage => "storage",
+            Capability::Transfer => "transfer",
+            Capability::CrossContract => "cross_contract",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A capability an export can reach
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapabilityUse {
+    pub capability: Capability,
+    /// The shortest chain reaching it without an authorization check first,
+    /// or None if every chain checks authorization
+    pub unauthorized: Option<Vec<u32>>,
+    /// Whether it was found by function name rather than by the calls made
+    pub by_name: bool,
+}
+
+/// The audit of one exported function
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportAudit {
+    pub export: u32,
+    /// The capabilities it can reach, in `Capability::ALL` order
+    pub uses: Vec<CapabilityUse>,
+}
+
+impl ExportAudit {
+    /// True if the export can reach a mutation without checking authorization first
+    pub fn flagged(&self) -> bool {
+        self.uses.iter().any(|u| u.capability.is_mutation() && u.unauthorized.is_some())
+    }
+
+    /// Format as `FLAG export: storage=unauthorized(a,b,c), cross_contract=auth`,
+    /// starting with `OK` instead if not flagged, and `none` if nothing is reached.
+    /// Capabilities found by function name are shown as `transfer(by name)`.
+    pub fn to_line(&self, data: &CallGraphData) -> String {
+        let uses: Vec<String> = self
+            .uses
+            .iter()
+            .map(|u| {
+                let capability =
+                    if u.by_name { format!("{}(by name)", u.capability) } else { u.capability.to_string() };
+                match &u.unauthorized {
+                    Some(chain) => {
+                        let names: Vec<String> = chain.iter().map(|&idx| data.function_name(idx)).collect();
+                        format!("{}=unauthorized({})", capability, names.join(","))
+                    }
+                    None => format!("{}=auth", capability),
+                }
+            })
+            .collect();
+        let status = if self.flagged() { "FLAG" } else { "OK" };
+        let uses = if uses.is_empty() { "none".to_string() } else { uses.join(", ") };
+        format!("{} {}: {}", status, data.function_name(self.export), uses)
+    }
+}
+
+/// Imports with one of the given host function names
+fn host_functions(data: &CallGraphData, names: &[&str]) -> Vec<u32> {
+    let mut funcs: Vec<u32> = data
+        .imported_functions
+        .iter()
+        .copied()
+        .filter(|idx| data.function_names.get(idx).is_some_and(|name| names.contains(&name.as_str())))
+        .collect();
+    funcs.sort();
+    funcs
+}
+
+/// Where a capability is exercised
+struct CapabilityEnds {
+    capability: Capability,
+    /// The functions exercising it
+    funcs: Vec<u32>,
+    /// Those of `funcs` that exercise it before a call in the function itself
+    /// can reach an authorization check
+    unguarded: Vec<u32>,
+    /// Whether `funcs` were found by function name
+    by_name: bool,
+}
+
+/// The defined functions that call `call` or `try_call` with a constant
+/// `transfer` function symbol, in function index order, and whether one of
+/// those calls comes before any call that can reach an authorization check
+fn transfer_callers(data: &CallGraphData, guards: &Guards) -> Vec<(u32, bool)> {
+    let cross_contract = host_functions(data, &CROSS_CONTRACT_FUNCTIONS);
+    let is_transfer = |edge: &CallEdge| {
+        let called = edge.site.and_then(|site| data.call_args.get(&site.offset)?.get(CALLED_FUNCTION_ARG).copied());
+        cross_contract.contains(&edge.callee) && symbol_val(called.flatten()).as_deref() == Some(TRANSFER_FUNCTION)
+    };
+    let mut callers: Vec<(u32, bool)> = data
+        .call_graph
+        .iter()
+        .filter_map(|(&caller, edges)| {
+            let first = edges.iter().position(is_transfer)?;
+            Some((caller, first < guards.first_guarded(edges)))
+        })
+        .collect();
+    callers.sort();
+    callers
+}
+
+/// The defined functions named like `transfer` that can make a cross-contract
+/// call, other than exports; the fallback for modules whose transfers aren't
+/// made with a constant function symbol
+fn transfer_functions_by_name(data: &CallGraphData) -> Vec<u32> {
+    let calling = functions_reaching(data, &host_functions(data, &CROSS_CONTRACT_FUNCTIONS));
+    let mut funcs: Vec<u32> = data
+        .all_function_indices
+        .iter()
+        .copied()
+        .filter(|idx| calling.contains(idx) && !data.is_root(*idx, &[RootKind::Export]))
+        .filter(|idx| {
+            data.function_names
+                .get(idx)
+                .is_some_and(|name| name.to_lowercase().contains(TRANSFER_FUNCTION))
+        })
+        .collect();
+    funcs.sort();
+    funcs
+}
+
+/// Find where a capability is exercised: the host functions themselves, and
+/// for transfers the functions calling another contract's `transfer`, or if
+/// there are none, the functions named like `transfer` that can make a
+/// cross-contract call
+fn capability_ends(data: &CallGraphData, capability: Capability, guards: &Guards) -> CapabilityEnds {
+    let host_ends = |funcs: Vec<u32>, by_name| CapabilityEnds { capability, unguarded: funcs.clone(), funcs, by_name };
+    match capability {
+        Capability::Storage => host_ends(host_functions(data, &STORAGE_FUNCTIONS), false),
+        Capability::CrossContract => host_ends(host_functions(data, &CROSS_CONTRACT_FUNCTIONS), false),
+        Capability::Transfer => {
+            let callers = transfer_callers(data, guards);
+            if callers.is_empty() {
+                return host_ends(transfer_functions_by_name(data), true);
+            }
+            CapabilityEnds {
+                capability,
+                funcs: callers.iter().map(|&(idx, _)| idx).collect(),
+                unguarded: callers.iter().filter(|(_, unguarded)| *unguarded).map(|&(idx, _)| idx).collect(),
+                by_name: false,
+            }
+        }
+    }
+}
+
+/// Audit the authorization of each export named in `src_filter` (every export
+/// if empty), in function index order.
+///
+/// For each capability an export can reach, this looks for a chain on which
+/// `require_auth` or `require_auth_for_args` is not called first, with the same
+/// notion of "first" as a `require_before` policy rule: the check is on the
+/// chain, or made by a call that comes before the one the chain continues with.
+/// Host functions are recognized by name, so imports must be named from an
+/// env.json file. A transfer is a `call` or `try_call` whose function symbol is
+/// a constant `transfer`, and is exercised by the function making that call,
+/// which may be the export itself.
+pub fn audit_exports(data: &CallGraphData, src_filter: &[String]) -> Vec<ExportAudit> {
+    let guards = Guards::new(data, &host_functions(data, &AUTH_FUNCTIONS));
+    let ends: Vec<CapabilityEnds> = Capability::ALL
+        .iter()
+        .map(|&capability| capability_ends(data, capability, &guards))
+        .collect();
+
+    data.start_functions(src_filter, Some(&[RootKind::Export]))
+        .into_iter()
+        .map(|export| {
+            let reachable = reachable_functions(data, &[export]);
+            let uses = ends
+                .iter()
+                .filter(|ends| ends.funcs.iter().any(|f| reachable.contains(f)))
+                .map(|ends| {
+                    let mut routes = unguarded_routes(data, export, &ends.unguarded, &guards);
+                    if ends.unguarded.contains(&export) {
+                        routes.push(vec![export]);
+                    }
+                    CapabilityUse {
+                        capability: ends.capability,
+                        unauthorized: routes.into_iter().min_by_key(|chain| chain.len()),
+                        by_name: ends.by_name,
+                    }
+                })
+                .collect();
+            ExportAudit { export, uses }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,139 @@
+// Copyright 2025 Stellar Development Foundation and contributors. Licensed
+// under the Apache License, Version 2.0. See the COPYING file at the root
+// of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
+
+use std::collections::HashMap;
+
+use super::*;
+use crate::parsing::parse_wasm_module;
+
+fn parse_wat(wat_source: &str) -> Vec<u8> {
+    wat::parse_str(wat_source).expect("Failed to parse WAT")
+}
+
+/// The env.json names of the imports used below
+fn env_symbols() -> HashMap<String, String> {
+    [
+        ("a.0", "require_auth"),
+        ("a.1", "require_auth_for_args"),
+        ("l.0", "put_contract_data"),
+        ("l.1", "get_contract_data"),
+        ("d.0", "call"),
+    ]
+    .into_iter()
+    .map(|(key, name)| (key.to_string(), name.to_string()))
+    .collect()
+}
+
+fn audit_lines(wat_source: &str) -> Vec<String> {
+    let data = parse_wasm_module(&parse_wat(wat_source), Some(&env_symbols())).unwrap();
+    audit_exports(&data, &[]).iter().map(|a| a.to_line(&data)).collect()
+}
+
+#[test]
+fn test_storage_authorization() {
+    let lines = audit_lines(
+        r#"
+        (module
+            (import "a" "0" (func $require_auth))
+            (import "a" "1" (func $require_auth_for_args))
+            (import "l" "0" (func $put))
+            (import "l" "1" (func $get))
+            (func $set_admin (export "set_admin") (call $require_auth) (call $write))
+            (func $transfer (export "transfer") (call $check) (call $write))
+            (func $mint (export "mint") (call $write) (call $require_auth))
+            (func $balance (export "balance") (call $get))
+            (func $check (call $require_auth_for_args))
+            (func $write (call $put))
+        )
+        "#,
+    );
+    assert_eq!(
+        lines,
+        vec![
+            "OK set_admin: storage=auth",
+            "OK transfer: storage=auth",
+            "FLAG mint: storage=unauthorized(mint,write,put_contract_data)",
+            "OK balance: none",
+        ]
+    );
+}
+
+#[test]
+fn test_transfers_and_cross_contract_calls() {
+    // 65154533130155790 is Symbol("transfer"), 696753673873934 Symbol("balance")
+    let lines = audit_lines(
+        r#"
+        (module
+            (import "a" "0" (func $require_auth))
+            (import "d" "0" (func $call (param i64 i64 i64) (result i64)))
+            (func $pay (export "pay") (call $token_transfer))
+            (func $withdraw (export "withdraw") (call $require_auth) (call $token_transfer))
+            (func $send (export "send") (drop (call $call (i64.const 0) (i64.const 65154533130155790) (i64.const 0))))
+            (func $refund (export "refund") (call $checked_transfer))
+            (func $price (export "price") (call $transfer_quote))
+            (func $token_transfer (drop (call $call (i64.const 0) (i64.const 65154533130155790) (i64.const 0))))
+            (func $checked_transfer
+                (call $require_auth)
+                (drop (call $call (i64.const 0) (i64.const 65154533130155790) (i64.const 0))))
+            (func $transfer_quote (drop (call $call (i64.const 0) (i64.const 696753673873934) (i64.const 0))))
+        )
+        "#,
+    );
+
+    // Unauthorized cross-contract calls are reported but only transfers are
+    // flagged, and only calls to `transfer` count, whatever the caller's name
+    assert_eq!(
+        lines,
+        vec![
+            "FLAG pay: transfer=unauthorized(pay,token_transfer), cross_contract=unauthorized(pay,token_transfer,call)",
+            "OK withdraw: transfer=auth, cross_contract=auth",
+            "FLAG send: transfer=unauthorized(send), cross_contract=unauthorized(send,call)",
+            "OK refund: transfer=auth, cross_contract=auth",
+            "OK price: cross_contract=unauthorized(price,transfer_quote,call)",
+        ]
+    );
+}
+
+#[test]
+fn test_transfers_by_name() {
+    // Without a constant function symbol, transfers are found by function name
+    let lines = audit_lines(
+        r#"
+        (module
+            (import "a" "0" (func $require_auth))
+            (import "d" "0" (func $call))
+            (func $pay (export "pay") (call $token_transfer))
+            (func $withdraw (export "withdraw") (call $require_auth) (call $token_transfer))
+            (func $price (export "price") (call $call))
+            (func $token_transfer (call $call))
+        )
+        "#,
+    );
+    assert_eq!(
+        lines,
+        vec![
+            "FLAG pay: transfer(by name)=unauthorized(pay,token_transfer), cross_contract=unauthorized(pay,token_transfer,call)",
+            "OK withdraw: transfer(by name)=auth, cross_contract=auth",
+            "OK price: cross_contract=unauthorized(price,call)",
+        ]
+    );
+}
+
+#[test]
+fn test_src_filter() {
+    let wasm = parse_wat(
+        r#"
+        (module
+            (import "l" "0" (func $put))
+            (func $a (export "a") (call $put))
+            (func $b (export "b") (call $put))
+        )
+        "#,
+    );
+    let data = parse_wasm_module(&wasm, Some(&env_symbols())).unwrap();
+    let audits = audit_exports(&data, &["b".to_string()]);
+    assert_eq!(audits.len(), 1);
+    assert!(audits[0].flagged());
+    assert_eq!(audits[0].uses[0].unauthorized, Some(vec![2, 0]));
+}
@@ -0,0 +1,73 @@
+// Copyright 2025 Stellar Development Foundation and contributors. Licensed
+// under the Apache License, Version 2.0. See the COPYING file at the root
+// of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
+
+use std::collections::BTreeSet;
+use std::fs;
+
+use crate::dot::Subgraph;
+use crate::parsing::CallGraphData;
+
+/// Parse a baseline or allowlist: one chain (or `caller,callee` edge) per
+/// line, ignoring blank lines and `#` comments
+pub fn parse_chain_set(text: &str) -> BTreeSet<String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Read a baseline or allowlist file
+pub fn read_chain_set(path: &str) -> Result<BTreeSet<String>, Box<dyn std::error::Error>> {
+    let content = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    Ok(parse_chain_set(&content))
+}
+
+/// Format a chain set for a baseline file, with a comment saying where it came from
+pub fn format_chain_set(set: &BTreeSet<String>, source: &str) -> String {
+    let mut text = format!("# wasm-call-graph baseline of {}\n", source);
+    for line in set {
+        text.push_str(line);
+        text.push('\n');
+    }
+    text
+}
+
+/// The calls of a subgraph as `caller,callee` lines
+pub fn edge_set(data: &CallGraphData, graph: &Subgraph) -> BTreeSet<String> {
+    graph
+        .calls
+        .iter()
+        .map(|(caller, callee)| format!("{},{}", data.function_name(*caller), data.function_name(*callee)))
+        .collect()
+}
+
+/// How the current chains differ from a baseline
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BaselineDiff {
+    /// Chains that are neither in the baseline nor allowed, sorted
+    pub added: Vec<String>,
+    /// Chains of the baseline that no longer appear, sorted
+    pub removed: Vec<String>,
+}
+
+/// Compare the current chains with a baseline. Allowed chains are never
+/// reported as added, so known-accepted chains don't need a new baseline.
+pub fn compare_chain_sets(
+    current: &BTreeSet<String>,
+    baseline: &BTreeSet<String>,
+    allowed: &BTreeSet<String>,
+) -> BaselineDiff {
+    BaselineDiff {
+        added: current
+            .iter()
+            .filter(|chain| !baseline.contains(*chain) && !allowed.contains(*chain))
+            .cloned()
+            .collect(),
+        removed: baseline.difference(current).cloned().collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,63 @@
+// Copyright 2025 Stellar Development Foundation and contributors. Licensed
+// under the Apache License, Version 2.0. See the COPYING file at the root
+// of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use crate::dot::reachable_subgraph;
+use crate::parsing::parse_wasm_module;
+
+fn parse_wat(wat_source: &str) -> Vec<u8> {
+    wat::parse_str(wat_source).expect("Failed to parse WAT")
+}
+
+fn set(lines: &[&str]) -> BTreeSet<String> {
+    lines.iter().map(|line| line.to_string()).collect()
+}
+
+#[test]
+fn test_round_trip() {
+    let chains = set(&["main,helper,put", "main,log"]);
+    let text = format_chain_set(&chains, "contract.wasm");
+    assert_eq!(text, "# wasm-call-graph baseline of contract.wasm\nmain,helper,put\nmain,log\n");
+    assert_eq!(parse_chain_set(&text), chains);
+
+    // Comments, blank lines and surrounding whitespace are ignored
+    assert_eq!(parse_chain_set("\n  main,log  \n# accepted\n"), set(&["main,log"]));
+}
+
+#[test]
+fn test_compare() {
+    let baseline = set(&["main,helper,put", "main,log"]);
+    let current = set(&["main,helper,put", "main,upgrade", "admin,upgrade"]);
+
+    let diff = compare_chain_sets(&current, &baseline, &BTreeSet::new());
+    assert_eq!(diff.added, vec!["admin,upgrade", "main,upgrade"]);
+    assert_eq!(diff.removed, vec!["main,log"]);
+
+    // Allowed chains are not reported
+    let diff = compare_chain_sets(&current, &baseline, &set(&["admin,upgrade"]));
+    assert_eq!(diff.added, vec!["main,upgrade"]);
+}
+
+#[test]
+fn test_edge_set() {
+    let wasm = parse_wat(
+        r#"
+        (module
+            (import "env" "put" (func $put))
+            (import "env" "log" (func $log))
+            (func $main (export "main") (call $helper) (call $helper) (call $log))
+            (func $helper (call $put))
+        )
+        "#,
+    );
+    let data = parse_wasm_module(&wasm, None).unwrap();
+
+    let edges = edge_set(&data, &reachable_subgraph(&data, None, None));
+    assert_eq!(edges, set(&["helper,put", "main,helper", "main,log"]));
+
+    // Only the calls on the way to the destinations
+    let put = data.end_functions(&["put".to_string()]);
+    let edges = edge_set(&data, &reachable_subgraph(&data, None, Some(&put)));
+    assert_eq!(edges, set(&["helper,put", "main,helper"]));
+}
@@ -0,0 +1,284 @@
+// Copyright 2025 Stellar Development Foundation and contributors. Licensed
+// under the Apache License, Version 2.0. See the COPYING file at the root
+// of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
+
+use std::collections::{HashMap, HashSet};
+use std::ops::ControlFlow;
+
+use crate::chains::{reachable_functions, Chain, ChainOptions, Hop};
+use crate::limits::{Limits, Truncation};
+use crate::parsing::{CallEdge, CallGraphData, RootKind};
+use crate::paths::{matches_path_pattern_tree, CallNode, PathOptions};
+
+/// A call into a function, seen from the callee
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CallerEdge {
+    pub caller: u32,
+    pub edge: CallEdge,
+}
+
+/// Build the inverted call graph: for each function, every call made to it,
+/// ordered by caller index and then by position in the caller
+pub fn invert_call_graph(data: &CallGraphData) -> HashMap<u32, Vec<CallerEdge>> {
+    let mut callers: HashMap<u32, Vec<CallerEdge>> = HashMap::new();
+    let mut caller_indices: Vec<u32> = data.call_graph.keys().copied().collect();
+    caller_indices.sort();
+    for caller in caller_indices {
+        for edge in &data.call_graph[&caller] {
+            callers.entry(edge.callee).or_default().push(CallerEdge { caller, edge: *edge });
+        }
+    }
+    callers
+}
+
+/// Collect every function that can reach one of `targets`, including the
+/// targets themselves: the mirror image of `reachable_functions`
+pub fn functions_reaching(data: &CallGraphData, targets: &[u32]) -> HashSet<u32> {
+    let callers = invert_call_graph(data);
+    let mut reaching: HashSet<u32> = HashSet::new();
+    let mut stack: Vec<u32> = targets.to_vec();
+    while let Some(func_idx) = stack.pop() {
+        if reaching.insert(func_idx) {
+            stack.extend(callers.get(&func_idx).into_iter().flatten().map(|c| c.caller));
+        }
+    }
+    reaching
+}
+
+/// Functions named in `dst_filter`, imported or defined, in index order
+fn destinations(data: &CallGraphData, dst_filter: &[String]) -> Vec<u32> {
+    let mut dsts: Vec<u32> = data
+        .imported_functions
+        .iter()
+        .chain(&data.all_function_indices)
+        .copied()
+        .filter(|idx| data.function_names.get(idx).is_some_and(|name| dst_filter.contains(name)))
+        .collect();
+    dsts.sort();
+    dsts
+}
+
+/// State shared across one backwards DFS from a destination
+struct CallerWalker<'a, F> {
+    data: &'a CallGraphData,
+    callers: &'a HashMap<u32, Vec<CallerEdge>>,
+    /// Functions a chain may start at
+    starts: HashSet<u32>,
+    /// Functions reachable from `starts`, the only callers a chain can go on through
+    from_starts: HashSet<u32>,
+    limits: Limits,
+    /// The chain so far in reverse: the destination first, and each function
+    /// with the call it makes to the one before it
+    current_path: Vec<(u32, Option<CallEdge>)>,
+    visited: HashSet<u32>,
+    visit: F,
+    chains: usize,
+    truncation: Truncation,
+}
+
+impl<F: FnMut(Chain) -> ControlFlow<()>> CallerWalker<'_, F> {
+    fn dfs(&mut self, func_idx: u32, edge: Option<CallEdge>) -> ControlFlow<()> {
+        self.current_path.push((func_idx, edge));
+        self.visited.insert(func_idx);
+
+        let callers: &[CallerEdge] = self.callers.get(&func_idx).map_or(&[], |c| c);
+        let at_max_depth = self.limits.at_max_depth(self.current_path.len());
+        // Callers no start can reach never lead back to one, so they are neither
+        // followed nor counted as cut
+        let cut_calls = if at_max_depth {
+            callers
+                .iter()
+                .filter(|c| !self.visited.contains(&c.caller) && self.from_starts.contains(&c.caller))
+                .count()
+        } else {
+            0
+        };
+        self.truncation.depth_cutoffs += cut_calls;
+        // Chains that would go on past the depth limit to a start are cut there
+        let cut_start = cut_calls > 0;
+
+        let mut flow = ControlFlow::Continue(());
+        if self.starts.contains(&func_idx) || cut_start {
+            if self.limits.at_max_chains(self.chains) {
+                self.truncation.chain_limit_reached = true;
+                flow = ControlFlow::Break(());
+            } else {
+                self.chains += 1;
+                let hops = self.forward_hops();
+                flow = (self.visit)(Chain { hops: &hops, truncated: false, truncated_start: cut_start });
+            }
+        }
+
+        if !at_max_depth {
+            for caller in callers {
+                if flow.is_break() {
+                    break;
+                }
+                if !self.visited.contains(&caller.caller) && self.from_starts.contains(&caller.caller) {
+                    flow = self.dfs(caller.caller, Some(caller.edge));
+                }
+            }
+        }
+
+        self.current_path.pop();
+        self.visited.remove(&func_idx);
+        flow
+    }
+
+    /// Turn the reversed path into the hops of a normal chain, where each hop
+    /// holds the call that reached it
+    fn forward_hops(&self) -> Vec<Hop> {
+        let path = &self.current_path;
+        (0..path.len())
+            .rev()
+            .map(|i| Hop {
+                func_index: path[i].0,
+                edge: if i + 1 < path.len() { path[i + 1].1 } else { None },
+            })
+            .collect()
+    }
+}
+
+/// Walk the inverted call graph backwards from each function in `dst_filter`,
+/// calling `visit` with every chain that ends there, as `walk_call_chains`
+/// would produce it with the same options. Only the callers of the
+/// destinations are explored, so this is much cheaper than a forward search.
+/// A chain that `max_depth` cuts before it gets back to a start is produced
+/// with the truncation marker in place of its start, once per cut.
+pub fn walk_caller_chains<F>(data: &CallGraphData, opts: &ChainOptions, visit: F) -> Truncation
+where
+    F: FnMut(Chain) -> ControlFlow<()>,
+{
+    let roots = match opts.roots {
+        None if opts.leaves_only => Some(&[RootKind::Export][..]),
+        roots => roots,
+    };
+    let dsts = destinations(data, opts.dst_filter);
+    let callers = invert_call_graph(data);
+    let starts = data.start_functions(opts.src_filter, roots);
+
+    let mut walker = CallerWalker {
+        data,
+        callers: &callers,
+        from_starts: reachable_functions(data, &starts),
+        starts: starts.into_iter().collect(),
+        limits: opts.limits,
+        current_path: Vec::new(),
+        visited: HashSet::new(),
+        visit,
+        chains: 0,
+        truncation: Truncation::default(),
+    };
+
+    for (i, &dst) in dsts.iter().enumerate() {
+        if opts.leaves_only && !walker.data.imported_functions.contains(&dst) {
+            continue;
+        }
+        if walker.dfs(dst, None).is_break() {
+            if walker.truncation.chain_limit_reached {
+                walker.truncation.skipped_starts = dsts.len() - i - 1;
+            }
+            break;
+        }
+    }
+    walker.truncation
+}
+
+/// State for building the caller trees of one module
+struct CallerTreeBuilder<'a> {
+    data: &'a CallGraphData,
+    callers: &'a HashMap<u32, Vec<CallerEdge>>,
+    /// Branches are only kept if they lead to one of these, if given
+    starts: Option<HashSet<u32>>,
+    limits: Limits,
+    on_branch: HashSet<u32>,
+    nodes: usize,
+    truncation: Truncation,
+}
+
+impl CallerTreeBuilder<'_> {
+    /// Build the tree of callers of a function. A caller already on the branch
+    /// is shown but not expanded again. Returns None if the subtree doesn't
+    /// contain any allowed starting function.
+    fn build(&mut self, func_idx: u32, edge: Option<CallEdge>, depth: usize) -> Option<CallNode> {
+        let mut node = CallNode::new(self.data.function_name(func_idx));
+        node.func_index = Some(func_idx);
+        node.edge = edge;
+        self.nodes += 1;
+
+        let is_start = self.starts.as_ref().is_none_or(|starts| starts.contains(&func_idx));
+        if self.on_branch.contains(&func_idx) {
+            return is_start.then_some(node);
+        }
+
+        let callers: &[CallerEdge] = self.callers.get(&func_idx).map_or(&[], |c| c);
+        if !callers.is_empty() && self.limits.at_max_depth(depth) {
+            self.truncation.depth_cutoffs += callers.len();
+            node.children.push(CallNode::truncation_marker());
+            return Some(node);
+        }
+
+        self.on_branch.insert(func_idx);
+        for caller in callers {
+            if self.limits.at_max_chains(self.nodes) {
+                self.truncation.chain_limit_reached = true;
+                node.children.push(CallNode::truncation_marker());
+                break;
+            }
+            if let Some(child) = self.build(caller.caller, Some(caller.edge), depth + 1) {
+                node.children.push(child);
+            }
+        }
+        self.on_branch.remove(&func_idx);
+
+        (is_start || !node.children.is_empty()).then_some(node)
+    }
+}
+
+/// Build a tree of callers for each function in `dst_filter`, rooted at the
+/// destination: in `X{A,B}`, A and B call X. Each node's `edge` is the call it
+/// makes to its parent. Branches that don't reach a function allowed by
+/// `src_filter` and `roots` are left out, unless neither is given. The path
+/// pattern is matched against the tree as in `generate_call_trees`.
+pub fn generate_caller_trees(
+    data: &CallGraphData,
+    dst_filter: &[String],
+    opts: &PathOptions,
+) -> (Vec<CallNode>, Truncation) {
+    let restricted = !opts.src_filter.is_empty() || opts.roots.is_some();
+    let callers = invert_call_graph(data);
+    let mut builder = CallerTreeBuilder {
+        data,
+        callers: &callers,
+        starts: restricted.then(|| data.start_functions(opts.src_filter, opts.roots).into_iter().collect()),
+        limits: opts.limits,
+        on_branch: HashSet::new(),
+        nodes: 0,
+        truncation: Truncation::default(),
+    };
+
+    let dsts = destinations(data, dst_filter);
+    let mut results = Vec::new();
+    for (i, &dst) in dsts.iter().enumerate() {
+        if builder.limits.at_max_chains(builder.nodes) {
+            builder.truncation.chain_limit_reached = true;
+            builder.truncation.skipped_starts = dsts.len() - i;
+            break;
+        }
+        let Some(tree) = builder.build(dst, None, 1) else {
+            continue;
+        };
+        match opts.path_pattern {
+            Some(pattern) if matches_path_pattern_tree(&tree, pattern) => {
+                results.extend(tree.filter_by_pattern(pattern));
+            }
+            Some(_) => {}
+            None => results.push(tree),
+        }
+    }
+
+    (results, builder.truncation)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,125 @@
+// Copyright 2025 Stellar Development Foundation and contributors. Licensed
+// under the Apache License, Version 2.0. See the COPYING file at the root
+// of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use crate::chains::{enumerate_call_chains, render_chain};
+use crate::labels::LabelOptions;
+use crate::parsing::parse_wasm_module;
+
+fn parse_wat(wat_source: &str) -> Vec<u8> {
+    wat::parse_str(wat_source).expect("Failed to parse WAT")
+}
+
+fn caller_chains(data: &CallGraphData, opts: &ChainOptions) -> Vec<String> {
+    let mut chains = Vec::new();
+    walk_caller_chains(data, opts, |chain| {
+        chains.push(render_chain(data, chain, opts));
+        ControlFlow::Continue(())
+    });
+    chains.sort();
+    chains
+}
+
+fn caller_trees(data: &CallGraphData, dst: &[&str], opts: &PathOptions) -> Vec<String> {
+    let dst: Vec<String> = dst.iter().map(|d| d.to_string()).collect();
+    let (trees, _) = generate_caller_trees(data, &dst, opts);
+    trees.iter().map(|tree| tree.render(data, &opts.labels)).collect()
+}
+
+const WAT: &str = r#"
+    (module
+        (import "env" "log" (func $log))
+        (import "env" "put" (func $put))
+        (func $main (export "main") (call $a) (call $b))
+        (func $other (export "other") (call $b) (call $put))
+        (func $a (call $b) (call $log))
+        (func $b (call $a) (call $put) (call $put))
+    )
+"#;
+
+#[test]
+fn test_invert_call_graph() {
+    let data = parse_wasm_module(&parse_wat(WAT), None).unwrap();
+    let callers = invert_call_graph(&data);
+
+    let of = |idx: u32| -> Vec<u32> { callers[&idx].iter().map(|c| c.caller).collect() };
+    assert_eq!(of(1), vec![3, 5, 5]);
+    assert_eq!(of(5), vec![2, 3, 4]);
+    assert!(!callers.contains_key(&2));
+}
+
+#[test]
+fn test_caller_chains_match_forward_chains() {
+    let data = parse_wasm_module(&parse_wat(WAT), None).unwrap();
+
+    let cases: Vec<(Vec<String>, Vec<String>, bool)> = vec![
+        (vec![], vec!["put".to_string()], false),
+        (vec![], vec!["log".to_string(), "b".to_string()], false),
+        (vec!["main".to_string()], vec!["put".to_string()], false),
+        (vec![], vec!["put".to_string(), "a".to_string()], true),
+    ];
+    for (src, dst, leaves_only) in &cases {
+        let opts = ChainOptions { src_filter: src, dst_filter: dst, leaves_only: *leaves_only, ..Default::default() };
+        let (forward, _) = enumerate_call_chains(&data, &opts);
+        assert!(!forward.is_empty());
+        assert_eq!(caller_chains(&data, &opts), forward);
+    }
+
+    // Call sites travel with the hops they reached
+    let dst = vec!["log".to_string()];
+    let opts = ChainOptions {
+        dst_filter: &dst,
+        labels: LabelOptions { offsets: true, ..Default::default() },
+        ..Default::default()
+    };
+    assert_eq!(caller_chains(&data, &opts), enumerate_call_chains(&data, &opts).0);
+}
+
+#[test]
+fn test_caller_chains_limits() {
+    let data = parse_wasm_module(&parse_wat(WAT), None).unwrap();
+    let dst = vec!["put".to_string()];
+    let opts = ChainOptions {
+        dst_filter: &dst,
+        limits: Limits { max_depth: Some(2), max_chains: None },
+        ..Default::default()
+    };
+
+    // Chains longer than two functions are cut, once per call to put in b
+    assert_eq!(caller_chains(&data, &opts), vec!["...,b,put", "...,b,put", "other,put"]);
+
+    // Also where the function cut at can't start a chain itself
+    let roots = [RootKind::Export];
+    let opts = ChainOptions { roots: Some(&roots), ..opts };
+    assert_eq!(caller_chains(&data, &opts), vec!["...,b,put", "...,b,put", "other,put"]);
+
+    // Callers main can't reach aren't followed, nor counted among the cut calls:
+    // other is skipped, and only main and a are cut above each b
+    let src = vec!["main".to_string()];
+    let opts = ChainOptions { src_filter: &src, roots: None, ..opts };
+    assert_eq!(caller_chains(&data, &opts), vec!["...,b,put", "...,b,put"]);
+    let truncation = walk_caller_chains(&data, &opts, |_| ControlFlow::Continue(()));
+    assert_eq!(truncation.depth_cutoffs, 4);
+}
+
+#[test]
+fn test_caller_trees() {
+    let data = parse_wasm_module(&parse_wat(WAT), None).unwrap();
+
+    // Every caller of put, then their callers; a is not expanded again below b
+    assert_eq!(
+        caller_trees(&data, &["put"], &PathOptions::default()),
+        vec!["put{other,b{main,other,a{main,b}},b{main,other,a{main,b}}}"]
+    );
+
+    // Restricting the sources drops branches that never get there
+    let src = vec!["main".to_string()];
+    let opts = PathOptions { src_filter: &src, ..Default::default() };
+    assert_eq!(caller_trees(&data, &["log"], &opts), vec!["log{a{main,b{main}}}"]);
+
+    // Same for roots
+    let roots = [RootKind::Export];
+    let opts = PathOptions { roots: Some(&roots), ..Default::default() };
+    assert_eq!(caller_trees(&data, &["log"], &opts), vec!["log{a{main,b{main,other}}}"]);
+}
@@ -0,0 +1,135 @@
+// Copyright 2025 Stellar Development Foundation and contributors. Licensed
+// under the Apache License, Version 2.0. See the COPYING file at the root
+// of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::chains::reachable_functions;
+use crate::parsing::{CallGraphData, RootKind};
+
+/// A row of the capability matrix: a host module, or one of its functions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapabilityRow {
+    pub module: String,
+    /// None for the row standing for the whole module
+    pub function: Option<u32>,
+    /// Whether each export of the matrix can reach it, in the same order
+    pub reached_by: Vec<bool>,
+}
+
+/// Which host modules and host functions each export can reach
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CapabilityMatrix {
+    /// The columns, in function index order
+    pub exports: Vec<u32>,
+    /// Each module that some export reaches, followed by its reached functions,
+    /// both sorted by name
+    pub rows: Vec<CapabilityRow>,
+}
+
+/// Build the capability matrix of the exports named in `src_filter` (every
+/// export if empty). Imports are grouped by the readable name of the module
+/// they come from in `module_names` (e.g. `ledger` for `l`), or by the raw
+/// module name if it isn't there.
+pub fn capability_matrix(
+    data: &CallGraphData,
+    module_names: &HashMap<String, String>,
+    src_filter: &[String],
+) -> CapabilityMatrix {
+    let module_of = |idx: &u32| {
+        let module = data.import_modules.get(idx).cloned().unwrap_or_default();
+        module_names.get(&module).cloned().unwrap_or(module)
+    };
+
+    let exports = data.start_functions(src_filter, Some(&[RootKind::Export]));
+    let reached: Vec<HashSet<u32>> = exports
+        .iter()
+        .map(|&export| {
+            let mut funcs = reachable_functions(data, &[export]);
+            funcs.retain(|idx| data.imported_functions.contains(idx));
+            funcs
+        })
+        .collect();
+
+    // Module name -> function name -> function index
+    let mut modules: BTreeMap<String, BTreeMap<String, u32>> = BTreeMap::new();
+    for idx in reached.iter().flatten() {
+        modules.entry(module_of(idx)).or_default().insert(data.function_name(*idx), *idx);
+    }
+
+    let mut rows = Vec::new();
+    for (module, funcs) in modules {
+        let reached_by = reached
+            .iter()
+            .map(|set| funcs.values().any(|idx| set.contains(idx)))
+            .collect();
+        rows.push(CapabilityRow { module: module.clone(), function: None, reached_by });
+        for idx in funcs.into_values() {
+            let reached_by = reached.iter().map(|set| set.contains(&idx)).collect();
+            rows.push(CapabilityRow { module: module.clone(), function: Some(idx), reached_by });
+        }
+    }
+    CapabilityMatrix { exports, rows }
+}
+
+impl CapabilityMatrix {
+    /// The cells of the matrix, header first: module, function (`*` for the
+    /// whole module), then `x` or nothing for each export
+    fn cells(&self, data: &CallGraphData) -> Vec<Vec<String>> {
+        let mut header = vec!["module".to_string(), "function".to_string()];
+        header.extend(self.exports.iter().map(|&idx| data.function_name(idx)));
+
+        let mut cells = vec![header];
+        for row in &self.rows {
+            let mut line = vec![row.module.clone(), row.function.map_or("*".to_string(), |idx| data.function_name(idx))];
+            line.extend(row.reached_by.iter().map(|&r| if r { "x" } else { "" }.to_string()));
+            cells.push(line);
+        }
+        cells
+    }
+
+    /// Format as a text table with aligned columns, unreached cells shown as `.`
+    pub fn to_table(&self, data: &CallGraphData) -> Vec<String> {
+        let cells = self.cells(data);
+        let mut widths = vec![0; cells[0].len()];
+        for line in &cells {
+            for (width, cell) in widths.iter_mut().zip(line) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+        cells
+            .iter()
+            .enumerate()
+            .map(|(i, line)| {
+                let padded: Vec<String> = line
+                    .iter()
+                    .zip(&widths)
+                    .enumerate()
+                    .map(|(col, (cell, &width))| {
+                        let cell = if i > 0 && col >= 2 && cell.is_empty() { "." } else { cell.as_str() };
+                        format!("{:width$}", cell, width = width)
+                    })
+                    .collect();
+                padded.join("  ").trim_end().to_string()
+            })
+            .collect()
+    }
+
+    /// Format as CSV lines, header first
+    pub fn to_csv(&self, data: &CallGraphData) -> Vec<String> {
+        let field = |cell: &String| {
+            if cell.contains([',', '"', '\n']) {
+                format!("\"{}\"", cell.replace('"', "\"\""))
+            } else {
+                cell.clone()
+            }
+        };
+        self.cells(data)
+            .iter()
+            .map(|line| line.iter().map(field).collect::<Vec<String>>().join(","))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,63 @@
+// Copyright 2025 Stellar Development Foundation and contributors. Licensed
+// under the Apache License, Version 2.0. See the COPYING file at the root
+// of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use crate::parsing::parse_wasm_module;
+
+fn parse_wat(wat_source: &str) -> Vec<u8> {
+    wat::parse_str(wat_source).expect("Failed to parse WAT")
+}
+
+const CONTRACT: &str = r#"
+    (module
+        (import "a" "0" (func $require_auth))
+        (import "l" "_" (func $put))
+        (import "l" "1" (func $has))
+        (import "x" "_" (func $log))
+        (func $init (export "init") (call $write) (call $log))
+        (func $set_admin (export "set_admin") (call $require_auth) (call $write) (call $has))
+        (func $get (export "get") (call $has))
+        (func $write (call $put))
+    )
+"#;
+
+fn module_names() -> HashMap<String, String> {
+    [("a", "address"), ("l", "ledger")]
+        .into_iter()
+        .map(|(export, name)| (export.to_string(), name.to_string()))
+        .collect()
+}
+
+#[test]
+fn test_capability_matrix() {
+    let data = parse_wasm_module(&parse_wat(CONTRACT), None).unwrap();
+    let matrix = capability_matrix(&data, &module_names(), &[]);
+    assert_eq!(matrix.exports, vec![4, 5, 6]);
+
+    // Modules missing from env.json keep their import module name
+    assert_eq!(
+        matrix.to_table(&data),
+        vec![
+            "module   function      init  set_admin  get",
+            "address  *             .     x          .",
+            "address  require_auth  .     x          .",
+            "ledger   *             x     x          x",
+            "ledger   has           .     x          x",
+            "ledger   put           x     x          .",
+            "x        *             x     .          .",
+            "x        log           x     .          .",
+        ]
+    );
+}
+
+#[test]
+fn test_csv_and_src_filter() {
+    let data = parse_wasm_module(&parse_wat(CONTRACT), None).unwrap();
+    let matrix = capability_matrix(&data, &module_names(), &["get".to_string()]);
+    assert_eq!(matrix.to_csv(&data), vec!["module,function,get", "ledger,*,x", "ledger,has,x"]);
+
+    let row = CapabilityRow { module: "a,b".to_string(), function: None, reached_by: vec![true] };
+    let matrix = CapabilityMatrix { exports: matrix.exports, rows: vec![row] };
+    assert_eq!(matrix.to_csv(&data)[1], "\"a,b\",*,x");
+}
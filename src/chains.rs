@@ -2,136 +2,198 @@
 // under the Apache License, Version 2.0. See the COPYING file at the root
 // of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
 
-use std::collections::{HashMap, HashSet};
+use std::collections::HashSet;
+use std::ops::ControlFlow;
+
+use crate::labels::{hop_label, LabelOptions};
+use crate::limits::{Limits, Truncation, TRUNCATION_MARKER};
+use crate::parsing::{CallEdge, CallGraphData, RootKind};
+
+/// Options controlling call chain enumeration
+#[derive(Debug, Clone, Default)]
+pub struct ChainOptions<'a> {
+    pub src_filter: &'a [String],
+    pub dst_filter: &'a [String],
+    /// Only start from roots of these kinds (all defined functions if None)
+    pub roots: Option<&'a [RootKind]>,
+    /// Only output the starting root and the import each chain ends at.
+    /// Starts from exports unless `roots` says otherwise.
+    pub leaves_only: bool,
+    pub limits: Limits,
+    pub labels: LabelOptions,
+}
 
-use crate::parsing::CallGraphData;
+/// One step of a call chain: a function and the call that reached it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hop {
+    pub func_index: u32,
+    /// None for the starting function
+    pub edge: Option<CallEdge>,
+}
 
-/// DFS to enumerate all call chains with recursion inhibition.
-/// Returns a vector of call chain strings.
-pub fn enumerate_call_chains(
-    data: &CallGraphData,
-    src_filter: &[String],
-    dst_filter: &[String],
-    leaves_only: bool,
-) -> Vec<String> {
-    let mut results = Vec::new();
+/// A call chain passed to the visitor of `walk_call_chains`
+#[derive(Debug, Clone, Copy)]
+pub struct Chain<'a> {
+    pub hops: &'a [Hop],
+    /// The last function has calls that `max_depth` kept us from following
+    pub truncated: bool,
+    /// The first function has callers that `max_depth` kept us from following,
+    /// in a chain found backwards from its end
+    pub truncated_start: bool,
+}
 
-    fn dfs(
-        func_idx: u32,
-        call_graph: &HashMap<u32, Vec<u32>>,
-        function_names: &HashMap<u32, String>,
-        imported_functions: &HashSet<u32>,
-        current_path: &mut Vec<u32>,
-        visited: &mut HashSet<u32>,
-        results: &mut Vec<String>,
-        dst_filter: &[String],
-        leaves_only: bool,
-    ) {
-        current_path.push(func_idx);
-        visited.insert(func_idx);
-
-        // Build the path string
-        let path_names: Vec<&str> = current_path
-            .iter()
-            .map(|idx| {
-                function_names
-                    .get(idx)
-                    .map(|s| s.as_str())
-                    .unwrap_or("unknown")
-            })
-            .collect();
+/// State shared across one DFS of the call graph
+struct ChainWalker<'a, F> {
+    data: &'a CallGraphData,
+    opts: &'a ChainOptions<'a>,
+    current_path: Vec<Hop>,
+    visited: HashSet<u32>,
+    visit: F,
+    chains: usize,
+    truncation: Truncation,
+}
+
+impl<F: FnMut(Chain) -> ControlFlow<()>> ChainWalker<'_, F> {
+    fn dfs(&mut self, func_idx: u32, edge: Option<CallEdge>) -> ControlFlow<()> {
+        self.current_path.push(Hop { func_index: func_idx, edge });
+        self.visited.insert(func_idx);
 
         // A leaf is an imported function (callable from runtime, has no callees in call graph)
-        let is_import = imported_functions.contains(&func_idx);
+        let is_import = self.data.imported_functions.contains(&func_idx);
 
         // Check if we should include this path based on dst_filter
-        let passes_dst_filter = if dst_filter.is_empty() {
-            true
-        } else {
-            path_names.last().map_or(false, |last| dst_filter.iter().any(|d| d == *last))
-        };
+        let passes_dst_filter = self.opts.dst_filter.is_empty()
+            || self
+                .data
+                .function_names
+                .get(&func_idx)
+                .is_some_and(|name| self.opts.dst_filter.iter().any(|d| d == name));
 
         // When leaves_only is true, only include paths that end at an import
-        let should_include = passes_dst_filter && (!leaves_only || is_import);
+        let should_include = passes_dst_filter && (!self.opts.leaves_only || is_import);
 
+        let callees: &[CallEdge] = self.data.call_graph.get(&func_idx).map_or(&[], |c| c);
+        let at_max_depth = self.opts.limits.at_max_depth(self.current_path.len());
+        let cut_calls = if at_max_depth {
+            callees.iter().filter(|e| !self.visited.contains(&e.callee)).count()
+        } else {
+            0
+        };
+        self.truncation.depth_cutoffs += cut_calls;
+
+        let mut flow = ControlFlow::Continue(());
         if should_include {
-            if leaves_only && path_names.len() > 1 {
-                // Only output start and end (leaf)
-                results.push(format!("{},{}", path_names[0], path_names[path_names.len() - 1]));
+            if self.opts.limits.at_max_chains(self.chains) {
+                self.truncation.chain_limit_reached = true;
+                flow = ControlFlow::Break(());
             } else {
-                results.push(path_names.join(","));
+                self.chains += 1;
+                flow = (self.visit)(Chain { hops: &self.current_path, truncated: cut_calls > 0, truncated_start: false });
             }
         }
 
         // Continue DFS to non-visited callees
-        if let Some(callees) = call_graph.get(&func_idx) {
-            for &callee in callees {
-                if !visited.contains(&callee) {
-                    dfs(
-                        callee,
-                        call_graph,
-                        function_names,
-                        imported_functions,
-                        current_path,
-                        visited,
-                        results,
-                        dst_filter,
-                        leaves_only,
-                    );
+        if !at_max_depth {
+            for edge in callees {
+                if flow.is_break() {
+                    break;
+                }
+                if !self.visited.contains(&edge.callee) {
+                    flow = self.dfs(edge.callee, Some(*edge));
                 }
             }
         }
 
-        current_path.pop();
-        visited.remove(&func_idx);
+        self.current_path.pop();
+        self.visited.remove(&func_idx);
+        flow
     }
+}
 
+/// DFS over all call chains with recursion inhibition, calling `visit` with
+/// each chain that passes the filters as soon as it is found. Chains are
+/// produced in DFS order: by starting function index, then by call order.
+/// Stops as soon as `visit` returns `Break` or `opts.limits.max_chains` is
+/// exceeded, and reports what the limits cut off.
+pub fn walk_call_chains<F>(data: &CallGraphData, opts: &ChainOptions, visit: F) -> Truncation
+where
+    F: FnMut(Chain) -> ControlFlow<()>,
+{
     // Determine which functions to start from
-    // When leaves_only is true, only start from exported functions
-    let candidate_functions: &[u32] = if leaves_only {
-        // Filter to only exported functions
-        &data.all_function_indices
-            .iter()
-            .filter(|idx| data.exported_functions.contains(idx))
-            .copied()
-            .collect::<Vec<_>>()
-    } else {
-        &data.all_function_indices
+    // When leaves_only is true, default to starting from exported functions
+    let roots = match opts.roots {
+        None if opts.leaves_only => Some(&[RootKind::Export][..]),
+        roots => roots,
     };
-
-    let start_functions: Vec<u32> = if src_filter.is_empty() {
-        candidate_functions.to_vec()
-    } else {
-        candidate_functions
-            .iter()
-            .filter(|&&idx| {
-                data.function_names
-                    .get(&idx)
-                    .map(|name| src_filter.iter().any(|s| s == name))
-                    .unwrap_or(false)
-            })
-            .copied()
-            .collect()
+    let start_functions = data.start_functions(opts.src_filter, roots);
+
+    let mut walker = ChainWalker {
+        data,
+        opts,
+        current_path: Vec::new(),
+        visited: HashSet::new(),
+        visit,
+        chains: 0,
+        truncation: Truncation::default(),
     };
 
-    for func_idx in start_functions {
-        let mut current_path: Vec<u32> = Vec::new();
-        let mut visited: HashSet<u32> = HashSet::new();
-        dfs(
-            func_idx,
-            &data.call_graph,
-            &data.function_names,
-            &data.imported_functions,
-            &mut current_path,
-            &mut visited,
-            &mut results,
-            dst_filter,
-            leaves_only,
-        );
+    for (i, &func_idx) in start_functions.iter().enumerate() {
+        if walker.dfs(func_idx, None).is_break() {
+            if walker.truncation.chain_limit_reached {
+                walker.truncation.skipped_starts = start_functions.len() - i - 1;
+            }
+            break;
+        }
     }
+    walker.truncation
+}
+
+/// Build the output string for a chain. A chain cut short by the depth limit
+/// ends with the truncation marker, or starts with it if cut at its start.
+pub fn render_chain(data: &CallGraphData, chain: Chain, opts: &ChainOptions) -> String {
+    let label = |hop: &Hop| hop_label(data, hop.func_index, hop.edge.as_ref(), &opts.labels);
+
+    let mut path_names: Vec<String> = match chain.hops {
+        // Only output start and end (leaf)
+        [first, .., last] if opts.leaves_only => vec![label(first), label(last)],
+        hops => hops.iter().map(label).collect(),
+    };
+    if chain.truncated_start {
+        path_names.insert(0, TRUNCATION_MARKER.to_string());
+    }
+    if chain.truncated {
+        path_names.push(TRUNCATION_MARKER.to_string());
+    }
+    path_names.join(",")
+}
 
+/// DFS to enumerate all call chains with recursion inhibition.
+/// Returns a sorted vector of call chain strings, and what the limits cut off.
+pub fn enumerate_call_chains(data: &CallGraphData, opts: &ChainOptions) -> (Vec<String>, Truncation) {
+    let mut results = Vec::new();
+    let truncation = walk_call_chains(data, opts, |chain| {
+        results.push(render_chain(data, chain, opts));
+        ControlFlow::Continue(())
+    });
     results.sort();
-    results
+    (results, truncation)
+}
+
+/// Collect every function reachable from `start_functions`, including the starts
+/// themselves. Unlike chain enumeration this visits each function once.
+pub fn reachable_functions(data: &CallGraphData, start_functions: &[u32]) -> HashSet<u32> {
+    let mut visited: HashSet<u32> = HashSet::new();
+    let mut stack: Vec<u32> = start_functions.to_vec();
+
+    while let Some(func_idx) = stack.pop() {
+        if visited.insert(func_idx) {
+            if let Some(callees) = data.call_graph.get(&func_idx) {
+                stack.extend(callees.iter().map(|edge| edge.callee).filter(|c| !visited.contains(c)));
+            }
+        }
+    }
+
+    visited
 }
 
 #[cfg(test)]
@@ -3,13 +3,35 @@
 // of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
 
 use super::*;
-use crate::parsing::{apply_implicit_calls, parse_wasm_module};
+use crate::labels::LabelOptions;
+use crate::limits::{Limits, Truncation};
+use crate::parsing::{apply_implicit_calls, parse_wasm_module, RootKind};
 use std::collections::HashMap;
+use std::ops::ControlFlow;
 
 fn parse_wat(wat_source: &str) -> Vec<u8> {
     wat::parse_str(wat_source).expect("Failed to parse WAT")
 }
 
+/// Shorthand for the common filters with default labels
+fn enumerate_call_chains(
+    data: &CallGraphData,
+    src_filter: &[String],
+    dst_filter: &[String],
+    leaves_only: bool,
+) -> Vec<String> {
+    super::enumerate_call_chains(
+        data,
+        &ChainOptions {
+            src_filter,
+            dst_filter,
+            leaves_only,
+            ..Default::default()
+        },
+    )
+    .0
+}
+
 #[test]
 fn test_simple_chain() {
     let wasm = parse_wat(
@@ -545,3 +567,312 @@ fn test_implicit_call_chains() {
     assert!(chains.contains(&"main,host_func,callback".to_string()));
     assert!(chains.contains(&"main,host_func,callback,helper".to_string()));
 }
+
+#[test]
+fn test_indirect_call_chains() {
+    let wasm = parse_wat(
+        r#"
+        (module
+            (type $t (func))
+            (table 2 funcref)
+            (elem (i32.const 0) $target $other)
+            (func $main (export "main") (call_indirect (type $t) (i32.const 0)))
+            (func $target (call $leaf))
+            (func $other (param i32))
+            (func $leaf)
+        )
+        "#,
+    );
+
+    let data = parse_wasm_module(&wasm, None).unwrap();
+    let chains = enumerate_call_chains(&data, &["main".to_string()], &[], false);
+
+    // Only $target matches the call's signature
+    assert!(chains.contains(&"main,target".to_string()));
+    assert!(chains.contains(&"main,target,leaf".to_string()));
+    assert!(!chains.iter().any(|c| c.contains("other")));
+    assert_eq!(chains.len(), 3);
+
+    // Indirect hops can be marked, direct hops are unchanged
+    let opts = ChainOptions {
+        src_filter: &["main".to_string()],
+        labels: LabelOptions {
+            mark_indirect: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let (chains, _) = super::enumerate_call_chains(&data, &opts);
+    assert!(chains.contains(&"main,target?".to_string()));
+    assert!(chains.contains(&"main,target?,leaf".to_string()));
+}
+
+#[test]
+fn test_chain_mark_recursive() {
+    let wasm = parse_wat(
+        r#"
+        (module
+            (import "env" "log" (func $log))
+            (func $main (export "main") (call $ping))
+            (func $ping (call $pong))
+            (func $pong (call $ping) (call $log))
+        )
+        "#,
+    );
+
+    let data = parse_wasm_module(&wasm, None).unwrap();
+    let opts = ChainOptions {
+        src_filter: &["main".to_string()],
+        dst_filter: &["log".to_string()],
+        labels: LabelOptions {
+            recursive: Some(crate::scc::recursive_functions(&data)),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let (chains, _) = super::enumerate_call_chains(&data, &opts);
+    assert_eq!(chains, vec!["main,ping*,pong*,log".to_string()]);
+}
+
+#[test]
+fn test_chain_offsets() {
+    let wasm = parse_wat(
+        r#"
+        (module
+            (import "env" "host" (func $host))
+            (func $a (export "a") (call $b))
+            (func $b (nop) (call $host))
+        )
+        "#,
+    );
+
+    let data = parse_wasm_module(&wasm, None).unwrap();
+    let site = |caller: u32| data.call_graph.get(&caller).unwrap()[0].site.unwrap();
+    let opts = ChainOptions {
+        src_filter: &["a".to_string()],
+        dst_filter: &["host".to_string()],
+        labels: LabelOptions {
+            offsets: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let (chains, _) = super::enumerate_call_chains(&data, &opts);
+
+    // The starting function has no call site; each later hop shows the call that reached it
+    let expected = format!("a,b[call@{:#x}#0],host[call@{:#x}#1]", site(1).offset, site(2).offset);
+    assert_eq!(chains, vec![expected]);
+}
+
+#[test]
+fn test_roots_filter() {
+    let wasm = parse_wat(
+        r#"
+        (module
+            (import "env" "log" (func $log))
+            (table funcref (elem $callback))
+            (start $init)
+            (func $init (call $helper))
+            (func $main (export "main") (call $helper))
+            (func $callback (call $log))
+            (func $helper (call $log))
+        )
+        "#,
+    );
+
+    let data = parse_wasm_module(&wasm, None).unwrap();
+    let chains_from = |roots: &[RootKind], leaves_only: bool| {
+        super::enumerate_call_chains(
+            &data,
+            &ChainOptions {
+                roots: Some(roots),
+                leaves_only,
+                ..Default::default()
+            },
+        )
+        .0
+    };
+
+    assert_eq!(chains_from(&[RootKind::Start], false), vec!["init", "init,helper", "init,helper,log"]);
+    assert_eq!(chains_from(&[RootKind::Table], false), vec!["callback", "callback,log"]);
+
+    // leaves_only starts from exports by default, but honours explicit roots
+    assert_eq!(enumerate_call_chains(&data, &[], &[], true), vec!["main,log"]);
+    assert_eq!(chains_from(&RootKind::ALL, true), vec!["callback,log", "init,log", "main,log"]);
+}
+
+#[test]
+fn test_reachable_functions() {
+    let wasm = parse_wat(
+        r#"
+        (module
+            (func $a (call $b) (call $c))
+            (func $b (call $a))
+            (func $c)
+            (func $d (call $a))
+        )
+        "#,
+    );
+
+    let data = parse_wasm_module(&wasm, None).unwrap();
+    let reachable = reachable_functions(&data, &[0]);
+    assert_eq!(reachable, HashSet::from([0, 1, 2]));
+    assert_eq!(reachable_functions(&data, &[]), HashSet::new());
+}
+
+#[test]
+fn test_walk_call_chains_order_and_hops() {
+    let wasm = parse_wat(
+        r#"
+        (module
+            (func $a (call $c) (call $b))
+            (func $b)
+            (func $c)
+        )
+        "#,
+    );
+
+    let data = parse_wasm_module(&wasm, None).unwrap();
+    let opts = ChainOptions::default();
+    let mut chains: Vec<Vec<u32>> = Vec::new();
+    let truncation = walk_call_chains(&data, &opts, |chain| {
+        chains.push(chain.hops.iter().map(|hop| hop.func_index).collect());
+        ControlFlow::Continue(())
+    });
+
+    // Depth-first, by function index and then call order, without sorting
+    assert_eq!(truncation, Truncation::default());
+    assert_eq!(chains, vec![vec![0], vec![0, 2], vec![0, 1], vec![1], vec![2]]);
+}
+
+#[test]
+fn test_walk_call_chains_stops_early() {
+    let wasm = parse_wat(
+        r#"
+        (module
+            (func $a (call $b) (call $c))
+            (func $b (call $c))
+            (func $c)
+        )
+        "#,
+    );
+
+    let data = parse_wasm_module(&wasm, None).unwrap();
+    let dst = ["c".to_string()];
+    let opts = ChainOptions {
+        dst_filter: &dst,
+        ..Default::default()
+    };
+    let mut seen = Vec::new();
+    let truncation = walk_call_chains(&data, &opts, |chain| {
+        seen.push(render_chain(&data, chain, &opts));
+        ControlFlow::Break(())
+    });
+
+    // Stopping from the visitor is not a truncation
+    assert!(!truncation.chain_limit_reached);
+    assert_eq!(seen, vec!["a,b,c".to_string()]);
+}
+
+#[test]
+fn test_render_chain_leaves_only() {
+    let wasm = parse_wat(
+        r#"
+        (module
+            (import "env" "log" (func $log))
+            (func $a (export "a") (call $b))
+            (func $b (call $log))
+        )
+        "#,
+    );
+
+    let data = parse_wasm_module(&wasm, None).unwrap();
+    let hops = [
+        Hop { func_index: 1, edge: None },
+        Hop { func_index: 2, edge: Some(data.call_graph.get(&1).unwrap()[0]) },
+        Hop { func_index: 0, edge: Some(data.call_graph.get(&2).unwrap()[0]) },
+    ];
+
+    let chain = |hops| Chain { hops, truncated: false, truncated_start: false };
+
+    assert_eq!(render_chain(&data, chain(&hops), &ChainOptions::default()), "a,b,log");
+    let leaves = ChainOptions {
+        leaves_only: true,
+        ..Default::default()
+    };
+    assert_eq!(render_chain(&data, chain(&hops), &leaves), "a,log");
+    assert_eq!(render_chain(&data, chain(&hops[..1]), &leaves), "a");
+    let cut = Chain { hops: &hops[..2], truncated: true, truncated_start: false };
+    assert_eq!(render_chain(&data, cut, &ChainOptions::default()), "a,b,...");
+    let cut = Chain { hops: &hops[1..], truncated: false, truncated_start: true };
+    assert_eq!(render_chain(&data, cut, &ChainOptions::default()), "...,b,log");
+}
+
+#[test]
+fn test_max_depth() {
+    let wasm = parse_wat(
+        r#"
+        (module
+            (func $a (call $b) (call $d))
+            (func $b (call $c) (call $d))
+            (func $c)
+            (func $d)
+        )
+        "#,
+    );
+
+    let data = parse_wasm_module(&wasm, None).unwrap();
+    let src = ["a".to_string()];
+    let opts = ChainOptions {
+        src_filter: &src,
+        limits: Limits {
+            max_depth: Some(2),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let (chains, truncation) = super::enumerate_call_chains(&data, &opts);
+
+    // b's calls to c and d are cut; d has no calls, so its chain is complete
+    assert_eq!(chains, vec!["a", "a,b,...", "a,d"]);
+    assert_eq!(truncation.depth_cutoffs, 2);
+    assert!(!truncation.chain_limit_reached);
+}
+
+#[test]
+fn test_max_chains() {
+    let wasm = parse_wat(
+        r#"
+        (module
+            (func $a (call $b))
+            (func $b (call $c))
+            (func $c)
+            (func $d)
+        )
+        "#,
+    );
+
+    let data = parse_wasm_module(&wasm, None).unwrap();
+    let limited = |max_chains| ChainOptions {
+        limits: Limits {
+            max_chains: Some(max_chains),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    // Chains from b are cut off after the first; c and d are never started
+    let (chains, truncation) = super::enumerate_call_chains(&data, &limited(4));
+    assert_eq!(chains, vec!["a", "a,b", "a,b,c", "b"]);
+    assert!(truncation.chain_limit_reached);
+    assert_eq!(truncation.skipped_starts, 2);
+    assert_eq!(
+        truncation.warnings(&limited(4).limits, "chains"),
+        vec!["output stopped at 4 chains; 2 starting functions not explored".to_string()]
+    );
+
+    // Exactly enough budget is not a truncation
+    let (chains, truncation) = super::enumerate_call_chains(&data, &limited(7));
+    assert_eq!(chains.len(), 7);
+    assert_eq!(truncation, Truncation::default());
+}
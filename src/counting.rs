@@ -0,0 +1,118 @@
+// Copyright 2025 Stellar Development Foundation and contributors. Licensed
+// under the Apache License, Version 2.0. See the COPYING file at the root
+// of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
+
+use crate::parsing::{CallGraphData, RootKind};
+use crate::scc::condense;
+
+/// Options controlling chain counting
+#[derive(Debug, Clone, Default)]
+pub struct CountOptions<'a> {
+    pub src_filter: &'a [String],
+    /// Functions to count chains to; all imports if empty
+    pub dst_filter: &'a [String],
+    /// Only count from roots of these kinds. Defaults to exports, or to all
+    /// defined functions when `src_filter` is given.
+    pub roots: Option<&'a [RootKind]>,
+}
+
+/// Number of call chains from one starting function to one destination
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainCount {
+    pub src_index: u32,
+    pub src: String,
+    pub dst_index: u32,
+    pub dst: String,
+    /// Saturates at `u128::MAX`
+    pub count: u128,
+    /// False if the chains pass through mutually recursive functions, in which
+    /// case `count` is a lower bound
+    pub exact: bool,
+}
+
+impl ChainCount {
+    /// Format as `src,dst,N`, or `src,dst,>=N` for a lower bound
+    pub fn to_line(&self) -> String {
+        let bound = if self.exact { "" } else { ">=" };
+        format!("{},{},{}{}", self.src, self.dst, bound, self.count)
+    }
+}
+
+/// Count the chains `enumerate_call_chains` would produce from each starting
+/// function to each destination, without enumerating them.
+///
+/// Strongly connected components are condensed into single nodes, and the
+/// chains are counted over the resulting DAG with one pass per starting
+/// function, counting each call site separately as enumeration does. Without
+/// mutual recursion this is exact; self-recursion is also exact, since
+/// enumeration never follows a call back into the current function.
+///
+/// A component with several functions is counted as if it were entered once
+/// and left once through each of its outgoing calls, while enumeration can
+/// take many different routes between its members. Counts through such a
+/// component, or to a function inside it, are therefore lower bounds and are
+/// marked as inexact.
+///
+/// Pairs without any chain are left out. Rows are ordered by starting
+/// function index, then by destination name.
+pub fn count_call_chains(data: &CallGraphData, opts: &CountOptions) -> Vec<ChainCount> {
+    let roots = match opts.roots {
+        None if opts.src_filter.is_empty() => Some(&[RootKind::Export][..]),
+        roots => roots,
+    };
+    let start_functions = data.start_functions(opts.src_filter, roots);
+
+    let destinations = data.end_functions(opts.dst_filter);
+
+    let condensation = condense(data);
+    let components = &condensation.components;
+    let mut results = Vec::new();
+
+    for &src in &start_functions {
+        // Chains reaching each component, and whether any of them went through
+        // a multi-function component on the way
+        let mut counts: Vec<u128> = vec![0; components.len()];
+        let mut inexact: Vec<bool> = vec![false; components.len()];
+        let start = condensation.component_of[&src];
+        counts[start] = 1;
+
+        // Components only call components with a lower index, so walking down
+        // from the start visits every caller before its callees
+        for component in (0..=start).rev() {
+            if counts[component] == 0 {
+                continue;
+            }
+            let leaves_inexact = inexact[component] || components[component].len() > 1;
+            for member in &components[component] {
+                for edge in data.call_graph.get(member).into_iter().flatten() {
+                    let target = condensation.component_of[&edge.callee];
+                    if target != component {
+                        counts[target] = counts[target].saturating_add(counts[component]);
+                        inexact[target] |= leaves_inexact;
+                    }
+                }
+            }
+        }
+
+        for &dst in &destinations {
+            let component = condensation.component_of[&dst];
+            if counts[component] == 0 {
+                continue;
+            }
+            let exact = !inexact[component] && (components[component].len() == 1 || dst == src);
+            results.push(ChainCount {
+                src_index: src,
+                src: data.function_name(src),
+                dst_index: dst,
+                dst: data.function_name(dst),
+                count: counts[component],
+                exact,
+            });
+        }
+    }
+
+    results
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,135 @@
+// Copyright 2025 Stellar Development Foundation and contributors. Licensed
+// under the Apache License, Version 2.0. See the COPYING file at the root
+// of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use crate::chains::{enumerate_call_chains, ChainOptions};
+use crate::parsing::parse_wasm_module;
+
+fn parse_wat(wat_source: &str) -> Vec<u8> {
+    wat::parse_str(wat_source).expect("Failed to parse WAT")
+}
+
+fn count_lines(data: &CallGraphData, opts: &CountOptions) -> Vec<String> {
+    count_call_chains(data, opts).iter().map(|c| c.to_line()).collect()
+}
+
+/// Count chains from `src` to `dst` by enumerating them
+fn enumerated(data: &CallGraphData, src: &str, dst: &str) -> u128 {
+    let src = [src.to_string()];
+    let dst = [dst.to_string()];
+    let opts = ChainOptions { src_filter: &src, dst_filter: &dst, ..Default::default() };
+    enumerate_call_chains(data, &opts).0.len() as u128
+}
+
+#[test]
+fn test_counts_match_enumeration_without_recursion() {
+    let wasm = parse_wat(
+        r#"
+        (module
+            (import "env" "log" (func $log))
+            (import "env" "put" (func $put))
+            (func $main (export "main") (call $a) (call $b) (call $b))
+            (func $other (export "other") (call $put))
+            (func $a (call $c) (call $log))
+            (func $b (call $c) (call $self))
+            (func $c (call $log) (call $put))
+            (func $self (call $self) (call $put))
+        )
+        "#,
+    );
+
+    let data = parse_wasm_module(&wasm, None).unwrap();
+    let counts = count_call_chains(&data, &CountOptions::default());
+
+    // Every export-to-import pair with a chain, each counted exactly
+    assert_eq!(
+        count_lines(&data, &CountOptions::default()),
+        vec!["main,log,4", "main,put,5", "other,put,1"]
+    );
+    for count in &counts {
+        assert!(count.exact);
+        assert_eq!(count.count, enumerated(&data, &count.src, &count.dst));
+    }
+}
+
+#[test]
+fn test_filters() {
+    let wasm = parse_wat(
+        r#"
+        (module
+            (import "env" "log" (func $log))
+            (func $main (export "main") (call $a) (call $a))
+            (func $a (call $b) (call $log))
+            (func $b (call $log))
+        )
+        "#,
+    );
+
+    let data = parse_wasm_module(&wasm, None).unwrap();
+
+    // --dst can name defined functions too
+    let dst = vec!["b".to_string()];
+    let opts = CountOptions { dst_filter: &dst, ..Default::default() };
+    assert_eq!(count_lines(&data, &opts), vec!["main,b,2"]);
+
+    // --src isn't limited to exports
+    let src = vec!["a".to_string()];
+    let opts = CountOptions { src_filter: &src, ..Default::default() };
+    assert_eq!(count_lines(&data, &opts), vec!["a,log,2"]);
+
+    // ...unless roots are asked for
+    let roots = [RootKind::Export];
+    let opts = CountOptions { src_filter: &src, roots: Some(&roots), ..Default::default() };
+    assert!(count_call_chains(&data, &opts).is_empty());
+}
+
+#[test]
+fn test_mutual_recursion_gives_lower_bound() {
+    let wasm = parse_wat(
+        r#"
+        (module
+            (import "env" "log" (func $log))
+            (import "env" "put" (func $put))
+            (func $main (export "main") (call $ping) (call $put))
+            (func $ping (call $pong) (call $log))
+            (func $pong (call $ping) (call $log))
+        )
+        "#,
+    );
+
+    let data = parse_wasm_module(&wasm, None).unwrap();
+    let counts = count_call_chains(&data, &CountOptions::default());
+
+    // main,ping,log / main,ping,pong,log are two chains; we only know there is at least one per call
+    assert_eq!(count_lines(&data, &CountOptions::default()), vec!["main,log,>=2", "main,put,1"]);
+    for count in &counts {
+        assert!(count.count <= enumerated(&data, &count.src, &count.dst));
+    }
+
+    // The starting function itself is exact, other members of its cycle are not
+    let src = vec!["ping".to_string()];
+    let dst = vec!["ping".to_string(), "pong".to_string()];
+    let opts = CountOptions { src_filter: &src, dst_filter: &dst, ..Default::default() };
+    assert_eq!(count_lines(&data, &opts), vec!["ping,ping,1", "ping,pong,>=1"]);
+}
+
+#[test]
+fn test_counts_too_many_chains_to_enumerate() {
+    // Each layer calls the next one twice, doubling the number of chains
+    let layers = 200;
+    let mut wat = String::from("(module (import \"env\" \"log\" (func $log))\n");
+    wat.push_str("(func $f0 (export \"main\") (call $f1) (call $f1))\n");
+    for i in 1..layers {
+        wat.push_str(&format!("(func $f{} (call $f{}) (call $f{}))\n", i, i + 1, i + 1));
+    }
+    wat.push_str(&format!("(func $f{} (call $log)))", layers));
+
+    let data = parse_wasm_module(&parse_wat(&wat), None).unwrap();
+    let counts = count_call_chains(&data, &CountOptions::default());
+
+    // 2^200 doesn't fit, so the count saturates
+    assert_eq!(counts.len(), 1);
+    assert_eq!(counts[0].count, u128::MAX);
+    assert!(counts[0].exact);
+}
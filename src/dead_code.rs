@@ -0,0 +1,66 @@
+// Copyright 2025 Stellar Development Foundation and contributors. Licensed
+// under the Apache License, Version 2.0. See the COPYING file at the root
+// of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
+
+use std::cmp::Reverse;
+
+use crate::chains::reachable_functions;
+use crate::parsing::CallGraphData;
+
+/// A function or import that no root can reach
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeadFunction {
+    pub func_index: u32,
+    pub name: String,
+    /// Body size in bytes; None for imports
+    pub size: Option<u32>,
+}
+
+impl DeadFunction {
+    pub fn is_import(&self) -> bool {
+        self.size.is_none()
+    }
+
+    /// Format as `name (N bytes)` or `name (import)`
+    pub fn to_line(&self) -> String {
+        match self.size {
+            Some(size) => format!("{} ({} bytes)", self.name, size),
+            None => format!("{} (import)", self.name),
+        }
+    }
+}
+
+/// Find every defined function and import that cannot be reached from an export,
+/// the start function, a table entry or a `ref.func` target.
+/// Defined functions come first, largest first; imports follow by name.
+pub fn find_dead_code(data: &CallGraphData) -> Vec<DeadFunction> {
+    // Imports can be roots too, e.g. when re-exported or placed in a table
+    let roots: Vec<u32> = data.roots.keys().copied().collect();
+    let reachable = reachable_functions(data, &roots);
+
+    let mut dead_functions: Vec<DeadFunction> = data
+        .all_function_indices
+        .iter()
+        .filter(|idx| !reachable.contains(idx))
+        .map(|&idx| DeadFunction {
+            func_index: idx,
+            name: data.function_name(idx),
+            size: Some(data.function_sizes.get(&idx).copied().unwrap_or(0)),
+        })
+        .collect();
+    dead_functions.sort_by_key(|f| (Reverse(f.size), f.name.clone()));
+
+    let mut dead_imports: Vec<DeadFunction> = data
+        .imported_functions
+        .iter()
+        .filter(|idx| !reachable.contains(idx))
+        .map(|&idx| DeadFunction { func_index: idx, name: data.function_name(idx), size: None })
+        .collect();
+    dead_imports.sort_by(|a, b| a.name.cmp(&b.name));
+
+    dead_functions.extend(dead_imports);
+    dead_functions
+}
+
+#[cfg(test)]
+mod tests;
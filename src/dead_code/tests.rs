@@ -0,0 +1,86 @@
+// Copyright 2025 Stellar Development Foundation and contributors. Licensed
+// under the Apache License, Version 2.0. See the COPYING file at the root
+// of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use crate::parsing::{apply_implicit_calls, parse_wasm_module};
+use std::collections::HashMap;
+
+fn parse_wat(wat_source: &str) -> Vec<u8> {
+    wat::parse_str(wat_source).expect("Failed to parse WAT")
+}
+
+fn dead_names(data: &CallGraphData) -> Vec<String> {
+    find_dead_code(data).into_iter().map(|f| f.name).collect()
+}
+
+#[test]
+fn test_dead_functions_and_imports() {
+    let wasm = parse_wat(
+        r#"
+        (module
+            (import "env" "log" (func $log))
+            (import "env" "unused" (func $unused))
+            (func $main (export "main") (call $helper))
+            (func $helper (call $log))
+            (func $orphan (call $orphan_callee) (call $unused))
+            (func $orphan_callee)
+        )
+        "#,
+    );
+
+    let data = parse_wasm_module(&wasm, None).unwrap();
+    let dead = find_dead_code(&data);
+
+    // Calls from dead code don't keep anything alive
+    assert_eq!(dead_names(&data), vec!["orphan", "orphan_callee", "unused"]);
+
+    // Sizes come from the function bodies; imports have none
+    assert_eq!(dead[0].size, data.function_sizes.get(&4).copied());
+    assert!(dead[0].size.unwrap() > dead[1].size.unwrap());
+    assert!(dead[2].is_import());
+    assert_eq!(dead[2].to_line(), "unused (import)");
+    assert_eq!(dead[1].to_line(), format!("orphan_callee ({} bytes)", dead[1].size.unwrap()));
+}
+
+#[test]
+fn test_all_root_kinds_keep_code_alive() {
+    let wasm = parse_wat(
+        r#"
+        (module
+            (table funcref (elem $in_table))
+            (start $init)
+            (func $init)
+            (func $in_table (call $from_table))
+            (func $from_table)
+            (func $in_code (export "main") (drop (ref.func $referenced)))
+            (func $referenced)
+        )
+        "#,
+    );
+
+    let data = parse_wasm_module(&wasm, None).unwrap();
+    assert!(find_dead_code(&data).is_empty());
+}
+
+#[test]
+fn test_implicit_calls_keep_callbacks_alive() {
+    let wasm = parse_wat(
+        r#"
+        (module
+            (import "env" "host" (func $host))
+            (func $main (export "main") (call $host))
+            (func $callback (call $helper))
+            (func $helper)
+        )
+        "#,
+    );
+
+    let mut data = parse_wasm_module(&wasm, None).unwrap();
+    assert_eq!(dead_names(&data), vec!["callback", "helper"]);
+
+    let mut implicit_calls = HashMap::new();
+    implicit_calls.insert("host".to_string(), "callback".to_string());
+    apply_implicit_calls(&mut data, &implicit_calls);
+    assert!(dead_names(&data).is_empty());
+}
@@ -0,0 +1,184 @@
+// Copyright 2025 Stellar Development Foundation and contributors. Licensed
+// under the Apache License, Version 2.0. See the COPYING file at the root
+// of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
+
+use crate::labels::{hop_label, LabelOptions};
+use crate::limits::TRUNCATION_MARKER;
+use crate::parsing::{CallEdge, CallGraphData};
+use crate::paths::CallNode;
+
+/// Escape text for a quoted Mermaid label. Mermaid reads `<...>` as HTML, which
+/// demangled Rust names like `drop_in_place<T>` are full of, so those characters
+/// become entity codes, and `#` too since it starts one.
+fn mermaid_text(s: &str) -> String {
+    s.replace('#', "#35;")
+        .replace('&', "#amp;")
+        .replace('<', "#lt;")
+        .replace('>', "#gt;")
+        .replace('"', "#quot;")
+}
+
+/// Escape text for a PlantUML name or message: `<` would start a creole tag
+/// such as `<b>` or `<u>`, so it is escaped with `~`, and `"` would end a name
+fn plantuml_text(s: &str) -> String {
+    s.replace('~', "~~").replace('<', "~<").replace('"', "'")
+}
+
+/// Render a call tree as a Mermaid flowchart inside a ```` ```mermaid ```` fence,
+/// ready to paste into Markdown. Each tree node gets its own box, so a function
+/// called from several places appears several times, just like in the tree.
+/// Arrows point from caller to callee; with `callers` the tree is a tree of
+/// callers, so they point from each child to its parent.
+pub fn mermaid_flowchart(data: &CallGraphData, tree: &CallNode, labels: &LabelOptions, callers: bool) -> String {
+    fn visit(
+        data: &CallGraphData,
+        node: &CallNode,
+        labels: &LabelOptions,
+        callers: bool,
+        next_id: &mut usize,
+        out: &mut String,
+    ) -> usize {
+        let id = *next_id;
+        *next_id += 1;
+        let label = match node.func_index {
+            Some(idx) => hop_label(data, idx, node.edge.as_ref(), labels),
+            None => node.name.clone(),
+        };
+        out.push_str(&format!("    n{}[\"{}\"]\n", id, mermaid_text(&label)));
+        for child in &node.children {
+            let child_id = visit(data, child, labels, callers, next_id, out);
+            if callers {
+                out.push_str(&format!("    n{} --> n{}\n", child_id, id));
+            } else {
+                out.push_str(&format!("    n{} --> n{}\n", id, child_id));
+            }
+        }
+        id
+    }
+
+    let mut out = String::from("```mermaid\nflowchart LR\n");
+    visit(data, tree, labels, callers, &mut 0, &mut out);
+    out.push_str("```\n");
+    out
+}
+
+/// Diagram languages for `sequence_diagram`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SequenceSyntax {
+    Mermaid,
+    PlantUml,
+}
+
+/// Describe the call behind a message: its kind, and with the matching label
+/// options its code offset and source location
+fn message(data: &CallGraphData, edge: &CallEdge, labels: &LabelOptions) -> String {
+    let mut text = edge.kind.to_string();
+    if let Some(site) = edge.site {
+        if labels.offsets {
+            text.push_str(&format!(" @{:#x}", site.offset));
+        }
+        if labels.locations {
+            if let Some(location) = data.call_site_location(&site) {
+                text.push_str(&format!(" {}", location));
+            }
+        }
+    }
+    text
+}
+
+/// State for writing one sequence diagram
+struct SequenceWriter<'a> {
+    data: &'a CallGraphData,
+    labels: &'a LabelOptions,
+    syntax: SequenceSyntax,
+    participants: Vec<u32>,
+    body: String,
+}
+
+impl SequenceWriter<'_> {
+    fn participant(&mut self, func_idx: u32) {
+        if !self.participants.contains(&func_idx) {
+            self.participants.push(func_idx);
+        }
+    }
+
+    /// Write the calls made by `node`, in order, each followed by the calls the
+    /// callee makes while it is active
+    fn calls(&mut self, node: &CallNode) {
+        let Some(caller) = node.func_index else {
+            return;
+        };
+        for child in &node.children {
+            let (Some(callee), Some(edge)) = (child.func_index, child.edge.as_ref()) else {
+                // A limit kept us from expanding the rest of the calls
+                let note = match self.syntax {
+                    SequenceSyntax::Mermaid => format!("    Note right of f{}: {}\n", caller, TRUNCATION_MARKER),
+                    SequenceSyntax::PlantUml => format!("note right of f{} : {}\n", caller, TRUNCATION_MARKER),
+                };
+                self.body.push_str(&note);
+                continue;
+            };
+            self.participant(callee);
+            let text = message(self.data, edge, self.labels);
+            let expands = !child.children.is_empty();
+            match self.syntax {
+                SequenceSyntax::Mermaid => {
+                    self.body.push_str(&format!("    f{}->>f{}: {}\n", caller, callee, mermaid_text(&text)));
+                    if expands {
+                        self.body.push_str(&format!("    activate f{}\n", callee));
+                        self.calls(child);
+                        self.body.push_str(&format!("    deactivate f{}\n", callee));
+                    }
+                }
+                SequenceSyntax::PlantUml => {
+                    self.body.push_str(&format!("f{} -> f{} : {}\n", caller, callee, plantuml_text(&text)));
+                    if expands {
+                        self.body.push_str(&format!("activate f{}\n", callee));
+                        self.calls(child);
+                        self.body.push_str(&format!("deactivate f{}\n", callee));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Render a call tree as a sequence diagram: one participant per function, in
+/// order of first appearance, and one message per call in the order the tree
+/// records them. A function is active while the calls it makes are shown.
+/// Mermaid output is wrapped in a ```` ```mermaid ```` fence for Markdown;
+/// PlantUML output is a plain `@startuml` block.
+pub fn sequence_diagram(data: &CallGraphData, tree: &CallNode, labels: &LabelOptions, syntax: SequenceSyntax) -> String {
+    let mut writer = SequenceWriter { data, labels, syntax, participants: Vec::new(), body: String::new() };
+    if let Some(root) = tree.func_index {
+        writer.participant(root);
+    }
+    writer.calls(tree);
+
+    let mut out = String::new();
+    match syntax {
+        SequenceSyntax::Mermaid => out.push_str("```mermaid\nsequenceDiagram\n"),
+        SequenceSyntax::PlantUml => out.push_str("@startuml\n"),
+    }
+    for idx in &writer.participants {
+        let mut name = data.function_name(*idx);
+        if labels.recursive.as_ref().is_some_and(|funcs| funcs.contains(idx)) {
+            name.push('*');
+        }
+        match syntax {
+            SequenceSyntax::Mermaid => out.push_str(&format!("    participant f{} as {}\n", idx, mermaid_text(&name))),
+            SequenceSyntax::PlantUml => {
+                out.push_str(&format!("participant \"{}\" as f{}\n", plantuml_text(&name), idx))
+            }
+        }
+    }
+    out.push_str(&writer.body);
+    match syntax {
+        SequenceSyntax::Mermaid => out.push_str("```\n"),
+        SequenceSyntax::PlantUml => out.push_str("@enduml\n"),
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests;
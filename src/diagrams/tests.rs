@@ -0,0 +1,102 @@
+// Copyright 2025 Stellar Development Foundation and contributors. Licensed
+// under the Apache License, Version 2.0. See the COPYING file at the root
+// of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use crate::limits::Limits;
+use crate::parsing::parse_wasm_module;
+use crate::paths::{generate_call_trees, PathOptions};
+
+fn parse_wat(wat_source: &str) -> Vec<u8> {
+    wat::parse_str(wat_source).expect("Failed to parse WAT")
+}
+
+fn main_tree(limits: Limits) -> (CallGraphData, CallNode) {
+    let wasm = parse_wat(
+        r#"
+        (module
+            (import "env" "log" (func $log))
+            (func $main (export "main") (call $helper) (call $log))
+            (func $helper (call $log) (call $log))
+        )
+        "#,
+    );
+    let data = parse_wasm_module(&wasm, None).unwrap();
+    let src = vec!["main".to_string()];
+    let (mut trees, _) = generate_call_trees(&data, &PathOptions { src_filter: &src, limits, ..Default::default() });
+    let tree = trees.remove(0);
+    (data, tree)
+}
+
+#[test]
+fn test_mermaid_flowchart() {
+    let (data, tree) = main_tree(Limits::default());
+    let labels = LabelOptions::default();
+
+    assert_eq!(
+        mermaid_flowchart(&data, &tree, &labels, false),
+        "```mermaid\nflowchart LR\n    n0[\"main\"]\n    n1[\"helper\"]\n    n2[\"log\"]\n    n1 --> n2\n    \
+         n3[\"log\"]\n    n1 --> n3\n    n0 --> n1\n    n4[\"log\"]\n    n0 --> n4\n```\n"
+    );
+
+    // Trees of callers point the other way
+    let flowchart = mermaid_flowchart(&data, &tree, &labels, true);
+    assert!(flowchart.contains("    n1 --> n0\n"));
+}
+
+#[test]
+fn test_sequence_diagrams() {
+    let (data, tree) = main_tree(Limits::default());
+    let labels = LabelOptions::default();
+
+    assert_eq!(
+        sequence_diagram(&data, &tree, &labels, SequenceSyntax::Mermaid),
+        "```mermaid\nsequenceDiagram\n    participant f1 as main\n    participant f2 as helper\n    participant f0 as log\n    \
+         f1->>f2: call\n    activate f2\n    f2->>f0: call\n    f2->>f0: call\n    deactivate f2\n    f1->>f0: call\n```\n"
+    );
+    assert_eq!(
+        sequence_diagram(&data, &tree, &labels, SequenceSyntax::PlantUml),
+        "@startuml\nparticipant \"main\" as f1\nparticipant \"helper\" as f2\nparticipant \"log\" as f0\n\
+         f1 -> f2 : call\nactivate f2\nf2 -> f0 : call\nf2 -> f0 : call\ndeactivate f2\nf1 -> f0 : call\n@enduml\n"
+    );
+
+    // Offsets go into the messages
+    let labels = LabelOptions { offsets: true, ..Default::default() };
+    let offset = data.call_graph[&1][0].site.unwrap().offset;
+    let diagram = sequence_diagram(&data, &tree, &labels, SequenceSyntax::Mermaid);
+    assert!(diagram.contains(&format!("f1->>f2: call @{:#x}\n", offset)));
+}
+
+#[test]
+fn test_truncated_tree() {
+    let (data, tree) = main_tree(Limits { max_depth: Some(1), max_chains: None });
+    let labels = LabelOptions::default();
+
+    assert_eq!(
+        sequence_diagram(&data, &tree, &labels, SequenceSyntax::Mermaid),
+        "```mermaid\nsequenceDiagram\n    participant f1 as main\n    Note right of f1: ...\n```\n"
+    );
+    assert!(mermaid_flowchart(&data, &tree, &labels, false).contains("n1[\"...\"]"));
+}
+
+#[test]
+fn test_generic_names() {
+    let (mut data, tree) = main_tree(Limits::default());
+    data.function_names.insert(1, "<impl Foo as Bar>::baz".to_string());
+    data.function_names.insert(2, "core::ptr::drop_in_place<Vec<u8>>".to_string());
+    data.function_names.insert(0, "a&b#\"c\"".to_string());
+    let labels = LabelOptions::default();
+
+    let flowchart = mermaid_flowchart(&data, &tree, &labels, false);
+    assert!(flowchart.contains("n0[\"#lt;impl Foo as Bar#gt;::baz\"]"));
+    assert!(flowchart.contains("n1[\"core::ptr::drop_in_place#lt;Vec#lt;u8#gt;#gt;\"]"));
+    assert!(flowchart.contains("n2[\"a#amp;b#35;#quot;c#quot;\"]"));
+
+    let diagram = sequence_diagram(&data, &tree, &labels, SequenceSyntax::Mermaid);
+    assert!(diagram.contains("participant f2 as core::ptr::drop_in_place#lt;Vec#lt;u8#gt;#gt;\n"));
+
+    let diagram = sequence_diagram(&data, &tree, &labels, SequenceSyntax::PlantUml);
+    assert!(diagram.contains("participant \"~<impl Foo as Bar>::baz\" as f1\n"));
+    assert!(diagram.contains("participant \"core::ptr::drop_in_place~<Vec~<u8>>\" as f2\n"));
+    assert!(diagram.contains("participant \"a&b#'c'\" as f0\n"));
+}
@@ -0,0 +1,232 @@
+// Copyright 2025 Stellar Development Foundation and contributors. Licensed
+// under the Apache License, Version 2.0. See the COPYING file at the root
+// of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::ops::ControlFlow;
+
+use serde::Serialize;
+
+use crate::baseline::edge_set;
+use crate::chains::{render_chain, walk_call_chains, ChainOptions};
+use crate::dot::reachable_subgraph;
+use crate::limits::{Limits, Truncation, TRUNCATION_MARKER};
+use crate::parsing::{CallGraphData, RootKind};
+use crate::paths::{generate_call_trees, PathOptions};
+use crate::records::TreeNodeRecord;
+
+/// Whether something appears only in the new module or only in the old one,
+/// or that the limits cut the chains or trees of a module short, so the diff
+/// may miss some of their changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Change {
+    Truncated,
+    Removed,
+    Added,
+}
+
+/// What changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffKind {
+    Import,
+    Function,
+    /// A `caller,callee` pair
+    Call,
+    /// A call chain from an export
+    Chain,
+    /// A node added to or removed from the call tree of an export, as its path
+    /// from the export followed by its subtree in paths mode syntax
+    Tree,
+}
+
+impl fmt::Display for DiffKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            DiffKind::Import => "import",
+            DiffKind::Function => "function",
+            DiffKind::Call => "call",
+            DiffKind::Chain => "chain",
+            DiffKind::Tree => "tree",
+        };
+        f.write_str(name)
+    }
+}
+
+/// What a diff compares of one module. Functions are identified by name, or
+/// by `func_N` when they have none, so unnamed functions are matched by index.
+#[derive(Debug, Clone, Default)]
+pub struct ModuleSummary {
+    pub imports: BTreeSet<String>,
+    pub functions: BTreeSet<String>,
+    pub calls: BTreeSet<String>,
+    pub chains: BTreeSet<String>,
+    /// The call tree of each export, by export name
+    pub trees: BTreeMap<String, TreeNodeRecord>,
+    /// Whether the limits left chains out of `chains`
+    pub chains_truncated: bool,
+    /// Whether the limits left calls out of `trees`
+    pub trees_truncated: bool,
+}
+
+/// Collect what a diff compares: every call, and the chains and call trees
+/// from each export, explored within `limits`. Also returns what the limits
+/// left out of the chains and of the trees.
+pub fn summarize_module(data: &CallGraphData, limits: Limits) -> (ModuleSummary, Truncation, Truncation) {
+    let roots = Some(&[RootKind::Export][..]);
+
+    let chain_opts = ChainOptions { roots, limits, ..Default::default() };
+    let mut chains = BTreeSet::new();
+    let chain_truncation = walk_call_chains(data, &chain_opts, |chain| {
+        chains.insert(render_chain(data, chain, &chain_opts));
+        ControlFlow::Continue(())
+    });
+
+    let path_opts = PathOptions { roots, limits, ..Default::default() };
+    let (trees, tree_truncation) = generate_call_trees(data, &path_opts);
+
+    let summary = ModuleSummary {
+        imports: data.imported_functions.iter().map(|&idx| data.function_name(idx)).collect(),
+        functions: data.all_function_indices.iter().map(|&idx| data.function_name(idx)).collect(),
+        calls: edge_set(data, &reachable_subgraph(data, None, None)),
+        chains,
+        trees: trees
+            .iter()
+            .filter_map(|tree| Some((data.function_name(tree.func_index?), TreeNodeRecord::new(data, tree))))
+            .collect(),
+        chains_truncated: chain_truncation != Truncation::default(),
+        trees_truncated: tree_truncation != Truncation::default(),
+    };
+    (summary, chain_truncation, tree_truncation)
+}
+
+/// One difference between two modules
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffEntry {
+    pub change: Change,
+    pub kind: DiffKind,
+    /// The name, call, chain or tree node; for truncations, which module was
+    /// cut short (`old` or `new`)
+    pub value: String,
+    /// For tree nodes, the node and its subtree in the module it comes from
+    pub tree: Option<TreeNodeRecord>,
+}
+
+impl DiffEntry {
+    /// Format as `+KIND VALUE`, `-KIND VALUE` or `!KIND VALUE`
+    pub fn to_line(&self) -> String {
+        let sign = match self.change {
+            Change::Truncated => '!',
+            Change::Removed => '-',
+            Change::Added => '+',
+        };
+        format!("{}{} {}", sign, self.kind, self.value)
+    }
+}
+
+/// The name a tree node is matched by: its function's, or the truncation marker
+fn node_label(node: &TreeNodeRecord) -> &str {
+    node.function.as_ref().map_or(TRUNCATION_MARKER, |f| f.name.as_str())
+}
+
+/// Render a tree node and its subtree in paths mode syntax, `X{A,B}`
+fn render_node(node: &TreeNodeRecord) -> String {
+    let label = node_label(node);
+    if node.children.is_empty() {
+        label.to_string()
+    } else {
+        let children: Vec<String> = node.children.iter().map(render_node).collect();
+        format!("{}{{{}}}", label, children.join(","))
+    }
+}
+
+/// Compare the children of a node present in both trees. Children are matched
+/// by name, in order, so a function called twice is matched twice; the ones
+/// left over were removed or added, and are reported with their subtree. The
+/// matched ones are compared in turn.
+fn diff_tree_nodes(path: &str, old: &TreeNodeRecord, new: &TreeNodeRecord, entries: &mut Vec<DiffEntry>) {
+    let mut unmatched_new: Vec<Option<&TreeNodeRecord>> = new.children.iter().map(Some).collect();
+    let report = |change, node: &TreeNodeRecord, entries: &mut Vec<DiffEntry>| {
+        entries.push(DiffEntry {
+            change,
+            kind: DiffKind::Tree,
+            value: format!("{},{}", path, render_node(node)),
+            tree: Some(node.clone()),
+        });
+    };
+    for old_child in &old.children {
+        let label = node_label(old_child);
+        let found = unmatched_new.iter_mut().find(|n| n.is_some_and(|n| node_label(n) == label));
+        match found.and_then(Option::take) {
+            Some(new_child) => diff_tree_nodes(&format!("{},{}", path, label), old_child, new_child, entries),
+            None => report(Change::Removed, old_child, entries),
+        }
+    }
+    for new_child in unmatched_new.into_iter().flatten() {
+        report(Change::Added, new_child, entries);
+    }
+}
+
+/// Everything that was added to or removed from `old` in `new`, ordered by
+/// kind (imports, functions, calls, chains, trees) and then by value, except
+/// tree nodes, which come in tree order. A call tree that changed shows up as
+/// the nodes that were removed from it or added to it. If the limits cut the
+/// chains or trees of either module short, the diff starts with a truncation
+/// entry for each.
+pub fn diff_modules(old: &ModuleSummary, new: &ModuleSummary) -> Vec<DiffEntry> {
+    let mut entries = Vec::new();
+    let truncations = [
+        (DiffKind::Chain, "old", old.chains_truncated),
+        (DiffKind::Chain, "new", new.chains_truncated),
+        (DiffKind::Tree, "old", old.trees_truncated),
+        (DiffKind::Tree, "new", new.trees_truncated),
+    ];
+    for (kind, module, truncated) in truncations {
+        if truncated {
+            entries.push(DiffEntry { change: Change::Truncated, kind, value: module.to_string(), tree: None });
+        }
+    }
+    let sets = [
+        (DiffKind::Import, &old.imports, &new.imports),
+        (DiffKind::Function, &old.functions, &new.functions),
+        (DiffKind::Call, &old.calls, &new.calls),
+        (DiffKind::Chain, &old.chains, &new.chains),
+    ];
+    for (kind, old_set, new_set) in sets {
+        let removed = old_set.difference(new_set).map(|value| (value, Change::Removed));
+        let added = new_set.difference(old_set).map(|value| (value, Change::Added));
+        let mut changes: Vec<(&String, Change)> = removed.chain(added).collect();
+        changes.sort();
+        entries.extend(changes.into_iter().map(|(value, change)| DiffEntry {
+            change,
+            kind,
+            value: value.clone(),
+            tree: None,
+        }));
+    }
+
+    let exports: BTreeSet<&String> = old.trees.keys().chain(new.trees.keys()).collect();
+    for export in exports {
+        match (old.trees.get(export), new.trees.get(export)) {
+            (Some(old_tree), Some(new_tree)) => diff_tree_nodes(export, old_tree, new_tree, &mut entries),
+            (old_tree, new_tree) => {
+                for (change, tree) in [(Change::Removed, old_tree), (Change::Added, new_tree)] {
+                    if let Some(tree) = tree {
+                        entries.push(DiffEntry {
+                            change,
+                            kind: DiffKind::Tree,
+                            value: render_node(tree),
+                            tree: Some(tree.clone()),
+                        });
+                    }
+                }
+            }
+        }
+    }
+    entries
+}
+
+#[cfg(test)]
+mod tests;
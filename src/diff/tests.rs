@@ -0,0 +1,125 @@
+// Copyright 2025 Stellar Development Foundation and contributors. Licensed
+// under the Apache License, Version 2.0. See the COPYING file at the root
+// of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use crate::parsing::parse_wasm_module;
+
+fn parse_wat(wat_source: &str) -> Vec<u8> {
+    wat::parse_str(wat_source).expect("Failed to parse WAT")
+}
+
+fn summary(wat_source: &str) -> ModuleSummary {
+    let data = parse_wasm_module(&parse_wat(wat_source), None).unwrap();
+    summarize_module(&data, Limits::default()).0
+}
+
+const OLD: &str = r#"
+    (module
+        (import "env" "log" (func $log))
+        (import "env" "put" (func $put))
+        (func $main (export "main") (call $helper))
+        (func $helper (call $log))
+        (func $gone (export "gone") (call $log))
+    )
+"#;
+
+#[test]
+fn test_diff_modules() {
+    // New imports shift every function index, but matching is by name
+    let new = summary(
+        r#"
+        (module
+            (import "env" "log" (func $log))
+            (import "env" "put" (func $put))
+            (import "env" "upgrade" (func $upgrade))
+            (func $main (export "main") (call $helper) (call $upgrade))
+            (func $helper (call $log) (call $put))
+        )
+        "#,
+    );
+    let lines: Vec<String> = diff_modules(&summary(OLD), &new).iter().map(|e| e.to_line()).collect();
+    assert_eq!(
+        lines,
+        vec![
+            "+import upgrade",
+            "-function gone",
+            "-call gone,log",
+            "+call helper,put",
+            "+call main,upgrade",
+            "-chain gone",
+            "-chain gone,log",
+            "+chain main,helper,put",
+            "+chain main,upgrade",
+            "-tree gone{log}",
+            "+tree main,helper,put",
+            "+tree main,upgrade",
+        ]
+    );
+}
+
+#[test]
+fn test_identical_modules() {
+    assert!(diff_modules(&summary(OLD), &summary(OLD)).is_empty());
+}
+
+#[test]
+fn test_tree_records() {
+    let new = summary(
+        r#"
+        (module
+            (import "env" "log" (func $log))
+            (func $main (export "main") (call $log))
+        )
+        "#,
+    );
+    let entries = diff_modules(&summary(OLD), &new);
+    let trees: Vec<String> = entries.iter().filter(|e| e.kind == DiffKind::Tree).map(|e| e.to_line()).collect();
+    assert_eq!(trees, vec!["-tree gone{log}", "-tree main,helper{log}", "+tree main,log"]);
+
+    // Each node comes from the module it belongs to, with its subtree
+    let removed = entries.iter().find(|e| e.value == "main,helper{log}").unwrap();
+    let tree = removed.tree.as_ref().unwrap();
+    assert_eq!(tree.function.as_ref().unwrap().name, "helper");
+    assert_eq!(tree.children[0].function.as_ref().unwrap().name, "log");
+    assert!(entries.iter().all(|e| (e.kind == DiffKind::Tree) == e.tree.is_some()));
+}
+
+#[test]
+fn test_repeated_calls() {
+    // A function called twice is matched twice, so only the extra call shows up
+    let tree = |calls: &str| {
+        summary(&format!(
+            r#"
+            (module
+                (import "env" "log" (func $log))
+                (import "env" "put" (func $put))
+                (func $main (export "main") {})
+            )
+            "#,
+            calls
+        ))
+    };
+    let old = tree("(call $log) (call $put)");
+    let new = tree("(call $log) (call $put) (call $log)");
+    let lines: Vec<String> = diff_modules(&old, &new)
+        .iter()
+        .filter(|e| e.kind == DiffKind::Tree)
+        .map(|e| e.to_line())
+        .collect();
+    assert_eq!(lines, vec!["+tree main,log"]);
+}
+
+#[test]
+fn test_truncated_diff() {
+    let data = parse_wasm_module(&parse_wat(OLD), None).unwrap();
+    let limits = Limits { max_depth: None, max_chains: Some(2) };
+    let (old, chain_truncation, tree_truncation) = summarize_module(&data, limits);
+    assert!(chain_truncation.chain_limit_reached);
+    assert!(tree_truncation.chain_limit_reached);
+    assert!(old.chains_truncated && old.trees_truncated);
+
+    let lines: Vec<String> = diff_modules(&old, &summary(OLD)).iter().map(|e| e.to_line()).collect();
+    assert_eq!(lines[..2], ["!chain old", "!tree old"]);
+    assert!(lines[2..].iter().all(|line| line.starts_with(['-', '+'])));
+}
@@ -0,0 +1,156 @@
+// Copyright 2025 Stellar Development Foundation and contributors. Licensed
+// under the Apache License, Version 2.0. See the COPYING file at the root
+// of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
+
+use std::collections::{BTreeSet, HashSet};
+
+use crate::callers::functions_reaching;
+use crate::chains::reachable_functions;
+use crate::parsing::{CallGraphData, CallKind, RootKind};
+use crate::paths::CallNode;
+
+/// The part of the call graph to draw: functions, and which caller/callee
+/// pairs to connect. Every call between a connected pair is drawn.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Subgraph {
+    pub functions: BTreeSet<u32>,
+    pub calls: HashSet<(u32, u32)>,
+}
+
+impl Subgraph {
+    pub fn is_empty(&self) -> bool {
+        self.functions.is_empty()
+    }
+
+    fn add_call(&mut self, caller: u32, callee: u32) {
+        self.functions.insert(caller);
+        self.functions.insert(callee);
+        self.calls.insert((caller, callee));
+    }
+}
+
+/// The functions and calls that lie on a way from a starting function to a
+/// destination: reachable from one of `start_functions` (or from anywhere if
+/// None), and reaching one of `end_functions` (or anything if None). With
+/// neither, this is the whole call graph, including functions that make and
+/// receive no calls. Like the traversals this is based on, it doesn't check
+/// that the way avoids revisiting functions, so it can include a few calls
+/// that no chain takes.
+pub fn reachable_subgraph(
+    data: &CallGraphData,
+    start_functions: Option<&[u32]>,
+    end_functions: Option<&[u32]>,
+) -> Subgraph {
+    let forward = start_functions.map(|starts| reachable_functions(data, starts));
+    let backward = end_functions.map(|ends| functions_reaching(data, ends));
+    let included = |idx: &u32| {
+        forward.as_ref().is_none_or(|f| f.contains(idx)) && backward.as_ref().is_none_or(|b| b.contains(idx))
+    };
+
+    let mut graph = Subgraph::default();
+    graph.functions.extend(data.imported_functions.iter().chain(&data.all_function_indices).filter(|idx| included(idx)));
+    for (caller, edges) in &data.call_graph {
+        if !included(caller) {
+            continue;
+        }
+        for edge in edges.iter().filter(|e| included(&e.callee)) {
+            graph.add_call(*caller, edge.callee);
+        }
+    }
+    graph
+}
+
+/// The functions and calls shown in a set of call trees, e.g. after `--paths`
+/// pattern filtering
+pub fn tree_subgraph(trees: &[CallNode]) -> Subgraph {
+    fn visit(node: &CallNode, graph: &mut Subgraph) {
+        let Some(parent) = node.func_index else {
+            return;
+        };
+        graph.functions.insert(parent);
+        for child in &node.children {
+            if let Some(func_idx) = child.func_index {
+                graph.add_call(parent, func_idx);
+            }
+            visit(child, graph);
+        }
+    }
+
+    let mut graph = Subgraph::default();
+    for tree in trees {
+        visit(tree, &mut graph);
+    }
+    graph
+}
+
+/// Quote a string for use as a DOT identifier or label
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Render a subgraph in Graphviz DOT format. Imports are boxes, exports double
+/// octagons, other roots (start function, table entries, `ref.func` targets)
+/// octagons, and internal functions ellipses. Calls of the same kind between
+/// the same pair of functions are merged into one edge labelled with their
+/// number; indirect calls are dashed and implicit calls dotted.
+pub fn render_dot(data: &CallGraphData, graph: &Subgraph, name: &str) -> String {
+    let mut dot = String::new();
+    dot.push_str(&format!("digraph {} {{\n", quote(name)));
+    dot.push_str("    node [shape=ellipse];\n");
+
+    for &func_idx in &graph.functions {
+        let label = data.function_name(func_idx);
+        let shape = if data.imported_functions.contains(&func_idx) {
+            Some("box")
+        } else if data.is_root(func_idx, &[RootKind::Export]) {
+            Some("doubleoctagon")
+        } else if data.is_root(func_idx, &RootKind::ALL) {
+            Some("octagon")
+        } else {
+            None
+        };
+        match shape {
+            Some(shape) => dot.push_str(&format!("    f{} [label={}, shape={}];\n", func_idx, quote(&label), shape)),
+            None => dot.push_str(&format!("    f{} [label={}];\n", func_idx, quote(&label))),
+        }
+    }
+
+    let mut callers: Vec<u32> = data.call_graph.keys().copied().filter(|c| graph.functions.contains(c)).collect();
+    callers.sort();
+    for caller in callers {
+        // Merge calls to the same callee with the same kind, in call order
+        let mut merged: Vec<(u32, CallKind, usize)> = Vec::new();
+        for edge in &data.call_graph[&caller] {
+            if !graph.calls.contains(&(caller, edge.callee)) {
+                continue;
+            }
+            match merged.iter_mut().find(|(callee, kind, _)| *callee == edge.callee && *kind == edge.kind) {
+                Some((_, _, count)) => *count += 1,
+                None => merged.push((edge.callee, edge.kind, 1)),
+            }
+        }
+
+        for (callee, kind, count) in merged {
+            let mut attrs: Vec<String> = Vec::new();
+            if count > 1 {
+                attrs.push(format!("label=\"×{}\"", count));
+            }
+            match kind {
+                CallKind::CallIndirect | CallKind::ReturnCallIndirect => attrs.push("style=dashed".to_string()),
+                CallKind::Implicit => attrs.push("style=dotted".to_string()),
+                CallKind::Call | CallKind::ReturnCall => {}
+            }
+            if attrs.is_empty() {
+                dot.push_str(&format!("    f{} -> f{};\n", caller, callee));
+            } else {
+                dot.push_str(&format!("    f{} -> f{} [{}];\n", caller, callee, attrs.join(", ")));
+            }
+        }
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+#[cfg(test)]
+mod tests;
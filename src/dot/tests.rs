@@ -0,0 +1,95 @@
+// Copyright 2025 Stellar Development Foundation and contributors. Licensed
+// under the Apache License, Version 2.0. See the COPYING file at the root
+// of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use crate::parsing::{apply_implicit_calls, parse_wasm_module};
+use crate::paths::{generate_call_trees, PathOptions};
+use std::collections::HashMap;
+
+fn parse_wat(wat_source: &str) -> Vec<u8> {
+    wat::parse_str(wat_source).expect("Failed to parse WAT")
+}
+
+const WAT: &str = r#"
+    (module
+        (type $t (func))
+        (import "env" "log" (func $log))
+        (import "env" "put" (func $put))
+        (table funcref (elem $handler))
+        (func $main (export "main") (call $helper) (call $helper) (call_indirect (type $t) (i32.const 0)))
+        (func $helper (call $log))
+        (func $handler (call $put))
+        (func $unused (call $log))
+    )
+"#;
+
+#[test]
+fn test_render_whole_graph() {
+    let data = parse_wasm_module(&parse_wat(WAT), None).unwrap();
+    let dot = render_dot(&data, &reachable_subgraph(&data, None, None), "module.wasm");
+
+    assert_eq!(
+        dot,
+        r#"digraph "module.wasm" {
+    node [shape=ellipse];
+    f0 [label="log", shape=box];
+    f1 [label="put", shape=box];
+    f2 [label="main", shape=doubleoctagon];
+    f3 [label="helper"];
+    f4 [label="handler", shape=octagon];
+    f5 [label="unused"];
+    f2 -> f3 [label="×2"];
+    f2 -> f4 [style=dashed];
+    f3 -> f0;
+    f4 -> f1;
+    f5 -> f0;
+}
+"#
+    );
+}
+
+#[test]
+fn test_reachable_subgraph() {
+    let data = parse_wasm_module(&parse_wat(WAT), None).unwrap();
+
+    // From main to log: handler and put are off the way, unused never starts
+    let graph = reachable_subgraph(&data, Some(&[2]), Some(&[0]));
+    assert_eq!(graph.functions.iter().copied().collect::<Vec<_>>(), vec![0, 2, 3]);
+    assert_eq!(graph.calls, HashSet::from([(2, 3), (3, 0)]));
+
+    // Only a destination: everything that reaches it
+    let graph = reachable_subgraph(&data, None, Some(&[1]));
+    assert_eq!(graph.functions.iter().copied().collect::<Vec<_>>(), vec![1, 2, 4]);
+
+    assert!(reachable_subgraph(&data, Some(&[5]), Some(&[1])).is_empty());
+}
+
+#[test]
+fn test_tree_subgraph_and_implicit_calls() {
+    let mut data = parse_wasm_module(&parse_wat(WAT), None).unwrap();
+    let mut implicit_calls = HashMap::new();
+    implicit_calls.insert("put".to_string(), "main".to_string());
+    apply_implicit_calls(&mut data, &implicit_calls);
+
+    let pattern = vec![vec!["handler".to_string()]];
+    let src = vec!["main".to_string()];
+    let opts = PathOptions { src_filter: &src, path_pattern: Some(&pattern), ..Default::default() };
+    let (trees, _) = generate_call_trees(&data, &opts);
+    let graph = tree_subgraph(&trees);
+    assert_eq!(graph.calls, HashSet::from([(2, 4)]));
+
+    // The callback makes everything main calls reachable from handler
+    let dot = render_dot(&data, &reachable_subgraph(&data, Some(&[4]), None), "m");
+    assert!(dot.contains("f1 -> f2 [style=dotted];"));
+    assert!(dot.contains("f3 -> f0;"));
+    assert!(!dot.contains("f5"));
+}
+
+#[test]
+fn test_labels_are_quoted() {
+    let mut data = parse_wasm_module(&parse_wat(WAT), None).unwrap();
+    data.function_names.insert(3, r#"say "hi" \o/"#.to_string());
+    let dot = render_dot(&data, &reachable_subgraph(&data, None, None), "m");
+    assert!(dot.contains(r#"f3 [label="say \"hi\" \\o/"];"#));
+}
@@ -0,0 +1,136 @@
+// Copyright 2025 Stellar Development Foundation and contributors. Licensed
+// under the Apache License, Version 2.0. See the COPYING file at the root
+// of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
+
+use std::collections::HashMap;
+use std::fmt;
+
+use gimli::{EndianSlice, LittleEndian, SectionId};
+
+/// Addresses at or above this are tombstones that the linker wrote for
+/// discarded functions; their line sequences don't describe real code.
+const TOMBSTONE_ADDRESS: u64 = 0xffff_fffe;
+
+/// A file and line in the module's source code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourceLocation<'a> {
+    pub file: &'a str,
+    pub line: u64,
+}
+
+impl fmt::Display for SourceLocation<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.file, self.line)
+    }
+}
+
+/// A run of code addresses that all map to the same source line
+#[derive(Debug, Clone)]
+struct LineRange {
+    start: u64,
+    end: u64,
+    file: usize,
+    line: u64,
+}
+
+/// Address-to-line lookup built from the DWARF line programs of a module.
+/// Addresses are offsets from the start of the code section contents.
+#[derive(Debug, Default)]
+pub struct LineTable {
+    files: Vec<String>,
+    /// Sorted by start address
+    ranges: Vec<LineRange>,
+}
+
+impl LineTable {
+    /// Find the source line for the instruction at `address`
+    pub fn lookup(&self, address: u64) -> Option<SourceLocation<'_>> {
+        let idx = self.ranges.partition_point(|r| r.start <= address);
+        let range = &self.ranges[idx.checked_sub(1)?];
+        if address < range.end {
+            Some(SourceLocation { file: &self.files[range.file], line: range.line })
+        } else {
+            None
+        }
+    }
+}
+
+/// Build a line table from the DWARF custom sections of a wasm module
+pub fn read_line_table(wasm_bytes: &[u8]) -> Result<LineTable, Box<dyn std::error::Error>> {
+    let mut sections: HashMap<&str, &[u8]> = HashMap::new();
+    for payload in wasmparser::Parser::new(0).parse_all(wasm_bytes) {
+        if let wasmparser::Payload::CustomSection(reader) = payload? {
+            if reader.name().starts_with(".debug_") {
+                sections.insert(reader.name(), reader.data());
+            }
+        }
+    }
+    Ok(parse_line_table(&sections)?)
+}
+
+/// Build a line table from the `.debug_*` custom sections of a module, keyed by
+/// section name. Returns an empty table when there is no line information.
+pub fn parse_line_table(sections: &HashMap<&str, &[u8]>) -> Result<LineTable, gimli::Error> {
+    let dwarf = gimli::Dwarf::load(|id: SectionId| -> Result<_, gimli::Error> {
+        let data = sections.get(id.name()).copied().unwrap_or(&[]);
+        Ok(EndianSlice::new(data, LittleEndian))
+    })?;
+
+    let mut table = LineTable::default();
+    let mut file_indices: HashMap<String, usize> = HashMap::new();
+
+    let mut units = dwarf.units();
+    while let Some(header) = units.next()? {
+        let unit = dwarf.unit(header)?;
+        let Some(program) = unit.line_program.clone() else {
+            continue;
+        };
+
+        // The row we are extending, as (start address, file index, line)
+        let mut pending: Option<(u64, usize, u64)> = None;
+        let mut sequence_is_dead = false;
+        let mut rows = program.rows();
+        while let Some((header, row)) = rows.next_row()? {
+            if pending.is_none() && !sequence_is_dead && row.address() >= TOMBSTONE_ADDRESS {
+                sequence_is_dead = true;
+            }
+
+            if let Some((start, file, line)) = pending.take() {
+                if row.address() > start && !sequence_is_dead {
+                    table.ranges.push(LineRange { start, end: row.address(), file, line });
+                }
+            }
+
+            if row.end_sequence() {
+                sequence_is_dead = false;
+                continue;
+            }
+
+            let line = row.line().map_or(0, |l| l.get());
+            let path = match row.file(header) {
+                Some(file) => {
+                    let name = dwarf.attr_string(&unit, file.path_name())?.to_string_lossy().into_owned();
+                    match file.directory(header) {
+                        Some(dir) if !name.starts_with('/') => {
+                            let dir = dwarf.attr_string(&unit, dir)?.to_string_lossy().into_owned();
+                            if dir.is_empty() { name } else { format!("{}/{}", dir, name) }
+                        }
+                        _ => name,
+                    }
+                }
+                None => "<unknown>".to_string(),
+            };
+            let file = *file_indices.entry(path).or_insert_with_key(|path| {
+                table.files.push(path.clone());
+                table.files.len() - 1
+            });
+            pending = Some((row.address(), file, line));
+        }
+    }
+
+    table.ranges.sort_by_key(|r| r.start);
+    Ok(table)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,191 @@
+// Copyright 2025 Stellar Development Foundation and contributors. Licensed
+// under the Apache License, Version 2.0. See the COPYING file at the root
+// of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
+
+use gimli::write::{Address, DwarfUnit, EndianVec, LineProgram, LineString, Sections};
+use gimli::{Encoding, Format, LineEncoding};
+
+use super::*;
+use crate::chains::{enumerate_call_chains, ChainOptions};
+use crate::labels::LabelOptions;
+use crate::parsing::parse_wasm_module;
+
+fn parse_wat(wat_source: &str) -> Vec<u8> {
+    wat::parse_str(wat_source).expect("Failed to parse WAT")
+}
+
+/// Build DWARF sections holding a single line sequence. Each row is
+/// (address, file, line); the sequence ends at `end`.
+fn build_debug_sections(rows: &[(u64, &str, u64)], end: u64) -> Vec<(&'static str, Vec<u8>)> {
+    let encoding = Encoding { format: Format::Dwarf32, version: 4, address_size: 4 };
+    let mut program = LineProgram::new(
+        encoding,
+        LineEncoding::default(),
+        LineString::String(b"/work".to_vec()),
+        None,
+        LineString::String(b"lib.rs".to_vec()),
+        None,
+    );
+    let dir = program.add_directory(LineString::String(b"/work/src".to_vec()));
+
+    program.begin_sequence(Some(Address::Constant(rows[0].0)));
+    for &(address, file, line) in rows {
+        let file_id = program.add_file(LineString::String(file.as_bytes().to_vec()), dir, None);
+        program.row().address_offset = address - rows[0].0;
+        program.row().file = file_id;
+        program.row().line = line;
+        program.generate_row();
+    }
+    program.end_sequence(end - rows[0].0);
+
+    let mut dwarf = DwarfUnit::new(encoding);
+    dwarf.unit.line_program = program;
+    let mut sections = Sections::new(EndianVec::new(gimli::LittleEndian));
+    dwarf.write(&mut sections).unwrap();
+
+    let mut result = Vec::new();
+    sections
+        .for_each(|id, data| -> Result<(), ()> {
+            if !data.slice().is_empty() {
+                result.push((id.name(), data.slice().to_vec()));
+            }
+            Ok(())
+        })
+        .unwrap();
+    result
+}
+
+/// Append custom sections to an encoded module
+fn append_custom_sections(wasm: &mut Vec<u8>, sections: &[(&str, Vec<u8>)]) {
+    fn leb(mut value: usize, out: &mut Vec<u8>) {
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                out.push(byte);
+                break;
+            }
+            out.push(byte | 0x80);
+        }
+    }
+
+    for (name, data) in sections {
+        let mut payload = Vec::new();
+        leb(name.len(), &mut payload);
+        payload.extend_from_slice(name.as_bytes());
+        payload.extend_from_slice(data);
+        wasm.push(0);
+        leb(payload.len(), wasm);
+        wasm.extend_from_slice(&payload);
+    }
+}
+
+fn line_table_for(rows: &[(u64, &str, u64)], end: u64) -> LineTable {
+    let sections = build_debug_sections(rows, end);
+    let map: HashMap<&str, &[u8]> = sections.iter().map(|(n, d)| (*n, d.as_slice())).collect();
+    parse_line_table(&map).unwrap()
+}
+
+#[test]
+fn test_line_table_lookup() {
+    let table = line_table_for(&[(0x10, "lib.rs", 3), (0x18, "util.rs", 40), (0x20, "lib.rs", 5)], 0x30);
+
+    assert_eq!(table.lookup(0x0f), None);
+    assert_eq!(table.lookup(0x10), Some(SourceLocation { file: "/work/src/lib.rs", line: 3 }));
+    assert_eq!(table.lookup(0x17), Some(SourceLocation { file: "/work/src/lib.rs", line: 3 }));
+    assert_eq!(table.lookup(0x18), Some(SourceLocation { file: "/work/src/util.rs", line: 40 }));
+    assert_eq!(table.lookup(0x2f), Some(SourceLocation { file: "/work/src/lib.rs", line: 5 }));
+    assert_eq!(table.lookup(0x30), None);
+}
+
+#[test]
+fn test_line_table_skips_tombstoned_sequences() {
+    let table = line_table_for(&[(0xffff_ffff, "dead.rs", 1)], 0xffff_ffff + 8);
+    assert_eq!(table.lookup(0xffff_ffff), None);
+}
+
+#[test]
+fn test_no_debug_sections() {
+    let table = parse_line_table(&HashMap::new()).unwrap();
+    assert_eq!(table.lookup(0), None);
+}
+
+#[test]
+fn test_function_and_call_site_locations() {
+    let mut wasm = parse_wat(
+        r#"
+        (module
+            (import "env" "log" (func $log))
+            (func $main (export "main") (nop) (call $helper))
+            (func $helper (nop) (call $log))
+        )
+        "#,
+    );
+
+    // Place each function's instructions on their own lines
+    let data = parse_wasm_module(&wasm, None).unwrap();
+    let main_start = *data.function_starts.get(&1).unwrap() as u64;
+    let main_call = data.call_graph.get(&1).unwrap()[0].site.unwrap().offset as u64;
+    let helper_start = *data.function_starts.get(&2).unwrap() as u64;
+    let helper_call = data.call_graph.get(&2).unwrap()[0].site.unwrap().offset as u64;
+    let sections = build_debug_sections(
+        &[
+            (main_start, "lib.rs", 10),
+            (main_call, "lib.rs", 12),
+            (helper_start, "helper.rs", 20),
+            (helper_call, "helper.rs", 21),
+        ],
+        helper_call + 4,
+    );
+    append_custom_sections(&mut wasm, &sections);
+
+    let mut data = parse_wasm_module(&wasm, None).unwrap();
+    // Line information is only read when asked for
+    assert!(data.function_location(1).is_none());
+    data.line_table = read_line_table(&wasm).unwrap();
+    assert_eq!(data.function_location(1).unwrap().to_string(), "/work/src/lib.rs:10");
+    assert_eq!(data.function_location(2).unwrap().to_string(), "/work/src/helper.rs:20");
+    // Imports have no code
+    assert!(data.function_location(0).is_none());
+
+    let opts = ChainOptions {
+        src_filter: &["main".to_string()],
+        dst_filter: &["log".to_string()],
+        labels: LabelOptions {
+            locations: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let (chains, _) = enumerate_call_chains(&data, &opts);
+    assert_eq!(
+        chains,
+        vec!["main@/work/src/lib.rs:10,helper@/work/src/lib.rs:12,log@/work/src/helper.rs:21".to_string()]
+    );
+}
+
+#[test]
+fn test_broken_debug_sections() {
+    let mut wasm = parse_wat(
+        r#"
+        (module
+            (import "env" "log" (func $log))
+            (func $main (export "main") (call $log))
+        )
+        "#,
+    );
+    // Cut the sections short, as a stripping tool gone wrong would
+    let sections: Vec<(&str, Vec<u8>)> = build_debug_sections(&[(0x10, "lib.rs", 3)], 0x20)
+        .into_iter()
+        .map(|(name, data)| (name, data[..data.len() / 2].to_vec()))
+        .collect();
+    append_custom_sections(&mut wasm, &sections);
+
+    assert!(read_line_table(&wasm).is_err());
+
+    // The default mode doesn't read them, so it still works
+    let data = parse_wasm_module(&wasm, None).unwrap();
+    let opts = ChainOptions { src_filter: &["main".to_string()], ..Default::default() };
+    let (chains, _) = enumerate_call_chains(&data, &opts);
+    assert_eq!(chains, vec!["main".to_string(), "main,log".to_string()]);
+}
@@ -0,0 +1,100 @@
+// Copyright 2025 Stellar Development Foundation and contributors. Licensed
+// under the Apache License, Version 2.0. See the COPYING file at the root
+// of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
+
+use std::collections::HashMap;
+
+use crate::chains::{Chain, ChainOptions, Hop};
+use crate::labels::hop_label;
+use crate::limits::TRUNCATION_MARKER;
+use crate::parsing::CallGraphData;
+
+/// What the count of a folded stack measures
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StackCost {
+    /// The number of chains with this stack: one per combination of call sites
+    Calls,
+    /// The body sizes in bytes of the stack's functions added up, per chain.
+    /// Imports have no body and count as one byte so they stay visible.
+    Size,
+}
+
+/// Collects call chains into folded stacks (`a;b;c 3`), the input format of
+/// flamegraph tools. Only whole chains make stacks: a flamegraph draws every
+/// prefix of a stack already, so a stack per prefix would count it again.
+/// Chains that only differ in their call sites fold into the same stack,
+/// adding up their counts.
+#[derive(Debug)]
+pub struct StackFolder {
+    cost: StackCost,
+    stacks: HashMap<String, u64>,
+}
+
+impl StackFolder {
+    pub fn new(cost: StackCost) -> Self {
+        StackFolder { cost, stacks: HashMap::new() }
+    }
+
+    /// Add a chain, labelled and shortened as `opts` says, unless it is the
+    /// prefix of a longer one. `;` would split a frame in two, so it is
+    /// replaced with `:` in labels.
+    pub fn add(&mut self, data: &CallGraphData, chain: Chain, opts: &ChainOptions) {
+        if !is_whole_chain(data, chain, opts) {
+            return;
+        }
+        let hops: Vec<&Hop> = match chain.hops {
+            [first, .., last] if opts.leaves_only => vec![first, last],
+            hops => hops.iter().collect(),
+        };
+        let mut frames: Vec<String> = hops
+            .iter()
+            .map(|hop| hop_label(data, hop.func_index, hop.edge.as_ref(), &opts.labels).replace(';', ":"))
+            .collect();
+        if chain.truncated_start {
+            frames.insert(0, TRUNCATION_MARKER.to_string());
+        }
+        if chain.truncated {
+            frames.push(TRUNCATION_MARKER.to_string());
+        }
+
+        let cost = match self.cost {
+            StackCost::Calls => 1,
+            StackCost::Size => chain
+                .hops
+                .iter()
+                .map(|hop| data.function_sizes.get(&hop.func_index).map_or(1, |&size| size as u64))
+                .fold(0, u64::saturating_add),
+        };
+        let count = self.stacks.entry(frames.join(";")).or_insert(0);
+        *count = count.saturating_add(cost);
+    }
+
+    /// The folded stacks as `stack count` lines, sorted by stack
+    pub fn into_lines(self) -> Vec<String> {
+        let mut stacks: Vec<(String, u64)> = self.stacks.into_iter().collect();
+        stacks.sort();
+        stacks.into_iter().map(|(stack, count)| format!("{} {}", stack, count)).collect()
+    }
+}
+
+/// Whether a chain is one the walk doesn't go on from. With a `dst_filter`
+/// every chain ends at a destination as asked; otherwise only chains cut by
+/// `max_depth` and chains whose last function calls nothing that isn't on the
+/// chain already, such as an import or a function making no calls, are whole.
+/// With `leaves_only` the walk only produces chains ending at imports.
+fn is_whole_chain(data: &CallGraphData, chain: Chain, opts: &ChainOptions) -> bool {
+    if !opts.dst_filter.is_empty() || chain.truncated {
+        return true;
+    }
+    let Some(last) = chain.hops.last() else {
+        return false;
+    };
+    data.call_graph
+        .get(&last.func_index)
+        .into_iter()
+        .flatten()
+        .all(|edge| chain.hops.iter().any(|hop| hop.func_index == edge.callee))
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,104 @@
+// Copyright 2025 Stellar Development Foundation and contributors. Licensed
+// under the Apache License, Version 2.0. See the COPYING file at the root
+// of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use crate::chains::walk_call_chains;
+use crate::limits::Limits;
+use crate::parsing::{parse_wasm_module, RootKind};
+use std::ops::ControlFlow;
+
+fn parse_wat(wat_source: &str) -> Vec<u8> {
+    wat::parse_str(wat_source).expect("Failed to parse WAT")
+}
+
+fn fold(data: &CallGraphData, opts: &ChainOptions, cost: StackCost) -> Vec<String> {
+    let mut folder = StackFolder::new(cost);
+    walk_call_chains(data, opts, |chain| {
+        folder.add(data, chain, opts);
+        ControlFlow::Continue(())
+    });
+    folder.into_lines()
+}
+
+const WAT: &str = r#"
+    (module
+        (import "env" "log" (func $log))
+        (func $main (export "main") (call $helper) (call $helper) (call $log))
+        (func $helper (call $log) (nop) (nop))
+    )
+"#;
+
+#[test]
+fn test_call_site_multiplicity() {
+    let data = parse_wasm_module(&parse_wat(WAT), None).unwrap();
+    let src = vec!["main".to_string()];
+    let opts = ChainOptions { src_filter: &src, ..Default::default() };
+
+    // Two calls to helper make two chains through it
+    assert_eq!(fold(&data, &opts, StackCost::Calls), vec!["main;helper;log 2", "main;log 1"]);
+}
+
+#[test]
+fn test_whole_chains_only() {
+    let wat = r#"
+        (module
+            (import "env" "log" (func $log))
+            (func $main (export "main") (call $a))
+            (func $a (call $b) (call $back) (call $done))
+            (func $b (call $log))
+            (func $back (call $a))
+            (func $done (nop))
+        )
+    "#;
+    let data = parse_wasm_module(&parse_wat(wat), None).unwrap();
+    let roots = [RootKind::Export];
+    let opts = ChainOptions { roots: Some(&roots), ..Default::default() };
+
+    // main, main;a and main;a;b are left out, since the stacks going on from
+    // them draw them; back only calls a function already on its chain
+    assert_eq!(
+        fold(&data, &opts, StackCost::Calls),
+        vec!["main;a;b;log 1", "main;a;back 1", "main;a;done 1"]
+    );
+}
+
+#[test]
+fn test_size_cost() {
+    let data = parse_wasm_module(&parse_wat(WAT), None).unwrap();
+    let src = vec!["main".to_string()];
+    let opts = ChainOptions { src_filter: &src, ..Default::default() };
+    let helper_size = data.function_sizes[&2] as u64;
+    let main_size = data.function_sizes[&1] as u64;
+
+    // Imports count as one byte
+    assert_eq!(
+        fold(&data, &opts, StackCost::Size),
+        vec![
+            format!("main;helper;log {}", 2 * (main_size + helper_size + 1)),
+            format!("main;log {}", main_size + 1),
+        ]
+    );
+}
+
+#[test]
+fn test_frames() {
+    let mut data = parse_wasm_module(&parse_wat(WAT), None).unwrap();
+    data.function_names.insert(2, "helper;v2".to_string());
+    let src = vec!["main".to_string()];
+
+    // Separators in names are replaced, and cut chains end in a marker frame
+    let opts = ChainOptions {
+        src_filter: &src,
+        limits: Limits { max_depth: Some(2), max_chains: None },
+        ..Default::default()
+    };
+    assert_eq!(
+        fold(&data, &opts, StackCost::Calls),
+        vec!["main;helper:v2;... 2", "main;log 1"]
+    );
+
+    let dst = vec!["log".to_string()];
+    let opts = ChainOptions { src_filter: &src, dst_filter: &dst, leaves_only: true, ..Default::default() };
+    assert_eq!(fold(&data, &opts, StackCost::Calls), vec!["main;log 3"]);
+}
@@ -0,0 +1,126 @@
+// Copyright 2025 Stellar Development Foundation and contributors. Licensed
+// under the Apache License, Version 2.0. See the COPYING file at the root
+// of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
+
+use std::collections::BTreeSet;
+
+use crate::parsing::{CallGraphData, EnvFunction, HostFunction};
+
+/// Format the arguments and result of a host function as
+/// `(k: Val, v: Val, t: StorageType) -> Void`
+pub fn signature(function: &EnvFunction) -> String {
+    let args: Vec<String> = function.args.iter().map(|arg| format!("{}: {}", arg.name, arg.ty)).collect();
+    format!("({}) -> {}", args.join(", "), function.ret)
+}
+
+/// The first sentence of a host function's docs
+pub fn doc_summary(function: &EnvFunction) -> &str {
+    let docs = function.docs.trim();
+    let end = docs
+        .find(". ")
+        .map(|i| i + 1)
+        .into_iter()
+        .chain(docs.find('\n'))
+        .min()
+        .unwrap_or(docs.len());
+    docs[..end].trim_end()
+}
+
+/// The protocol versions a host function is available in, as `21..`, `..19`
+/// or `19..19`, or None if it is available in all of them
+pub fn protocol_range(function: &EnvFunction) -> Option<String> {
+    let bound = |version: Option<u32>| version.map_or(String::new(), |v| v.to_string());
+    (function.min_supported_protocol.is_some() || function.max_supported_protocol.is_some()).then(|| {
+        format!("{}..{}", bound(function.min_supported_protocol), bound(function.max_supported_protocol))
+    })
+}
+
+/// What to show about host functions in chains and trees
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HostInfo {
+    /// Arguments and result, e.g. `put_contract_data(k: Val, v: Val, t: StorageType) -> Void`
+    Signature,
+    /// The first sentence of the docs, quoted
+    Docs,
+}
+
+/// Render what `info` asks for about a host function, to follow its name.
+/// Empty for functions without docs.
+pub fn host_info_suffix(function: &EnvFunction, info: HostInfo) -> String {
+    match info {
+        HostInfo::Signature => signature(function),
+        HostInfo::Docs if function.docs.trim().is_empty() => String::new(),
+        HostInfo::Docs => format!(" {:?}", doc_summary(function)),
+    }
+}
+
+/// Everything known about one import of a module
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportDescription<'a> {
+    pub func_idx: u32,
+    /// The import's `module.field`, e.g. `l._`
+    pub import: String,
+    /// What env.json says about it, if anything
+    pub host: Option<&'a HostFunction>,
+    /// The defined functions that call it directly, sorted by name
+    pub callers: Vec<String>,
+}
+
+/// Describe the imports named in `names` (every import if empty), sorted by name
+pub fn describe_imports<'a>(data: &'a CallGraphData, names: &[String]) -> Vec<ImportDescription<'a>> {
+    let mut imports: Vec<(String, u32)> = data
+        .imported_functions
+        .iter()
+        .map(|idx| (data.function_name(*idx), *idx))
+        .filter(|(name, _)| names.is_empty() || names.contains(name))
+        .collect();
+    imports.sort();
+
+    imports
+        .into_iter()
+        .map(|(_, idx)| {
+            let callers: BTreeSet<String> = data
+                .call_graph
+                .iter()
+                .filter(|(_, edges)| edges.iter().any(|edge| edge.callee == idx))
+                .map(|(caller, _)| data.function_name(*caller))
+                .collect();
+            ImportDescription {
+                func_idx: idx,
+                import: format!("{}.{}", data.import_modules[&idx], data.import_fields[&idx]),
+                host: data.host_functions.get(&idx),
+                callers: callers.into_iter().collect(),
+            }
+        })
+        .collect()
+}
+
+impl ImportDescription<'_> {
+    /// Format as the import's name followed by indented `key: value` lines
+    pub fn to_lines(&self, data: &CallGraphData) -> Vec<String> {
+        let name = data.function_name(self.func_idx);
+        let mut lines = vec![name.clone()];
+        match self.host {
+            Some(host) => {
+                lines.push(format!("  import: {} ({})", self.import, host.module));
+                lines.push(format!("  signature: {}{}", host.function.name, signature(&host.function)));
+                if let Some(range) = protocol_range(&host.function) {
+                    lines.push(format!("  protocol: {}", range));
+                }
+            }
+            None => lines.push(format!("  import: {}", self.import)),
+        }
+        if !self.callers.is_empty() {
+            lines.push(format!("  called by: {}", self.callers.join(", ")));
+        }
+        if let Some(host) = self.host.filter(|host| !host.function.docs.trim().is_empty()) {
+            let mut docs = host.function.docs.trim().lines();
+            lines.push(format!("  docs: {}", docs.next().unwrap_or_default()));
+            lines.extend(docs.map(|line| format!("    {}", line)));
+        }
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,112 @@
+// Copyright 2025 Stellar Development Foundation and contributors. Licensed
+// under the Apache License, Version 2.0. See the COPYING file at the root
+// of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
+
+use std::collections::HashMap;
+
+use super::*;
+use crate::parsing::{apply_env_functions, parse_wasm_module, EnvConfig};
+
+fn parse_wat(wat_source: &str) -> Vec<u8> {
+    wat::parse_str(wat_source).expect("Failed to parse WAT")
+}
+
+const ENV: &str = r#"
+{
+    "modules": [
+        {
+            "name": "ledger",
+            "export": "l",
+            "functions": [
+                {
+                    "export": "_",
+                    "name": "put_contract_data",
+                    "args": [
+                        {"name": "k", "type": "Val"},
+                        {"name": "v", "type": "Val"},
+                        {"name": "t", "type": "StorageType"}
+                    ],
+                    "return": "Void",
+                    "docs": "Stores a value. Overwrites any previous one.\nSee also del_contract_data."
+                },
+                {
+                    "export": "9",
+                    "name": "extend_contract_code_ttl",
+                    "args": [],
+                    "return": "Void",
+                    "min_supported_protocol": 21
+                }
+            ]
+        }
+    ]
+}
+"#;
+
+fn host_functions() -> HashMap<String, HostFunction> {
+    let config: EnvConfig = serde_json::from_str(ENV).unwrap();
+    let module = &config.modules[0];
+    module
+        .functions
+        .iter()
+        .map(|function| {
+            let host = HostFunction {
+                module: module.name.clone().unwrap(),
+                module_export: module.export.clone(),
+                function: function.clone(),
+            };
+            (format!("{}.{}", module.export, function.export), host)
+        })
+        .collect()
+}
+
+#[test]
+fn test_host_function_text() {
+    let functions = host_functions();
+    let put = &functions["l._"].function;
+    assert_eq!(signature(put), "(k: Val, v: Val, t: StorageType) -> Void");
+    assert_eq!(doc_summary(put), "Stores a value.");
+    assert_eq!(host_info_suffix(put, HostInfo::Docs), " \"Stores a value.\"");
+    assert_eq!(protocol_range(put), None);
+
+    let extend = &functions["l.9"].function;
+    assert_eq!(signature(extend), "() -> Void");
+    assert_eq!(host_info_suffix(extend, HostInfo::Docs), "");
+    assert_eq!(protocol_range(extend).unwrap(), "21..");
+}
+
+#[test]
+fn test_describe_imports() {
+    let wasm = parse_wat(
+        r#"
+        (module
+            (import "l" "_" (func $put))
+            (import "env" "log" (func $log))
+            (func $main (export "main") (call $put) (call $helper))
+            (func $helper (call $put) (call $log))
+        )
+        "#,
+    );
+    let mut data = parse_wasm_module(&wasm, None).unwrap();
+    apply_env_functions(&mut data, &host_functions());
+
+    let imports = describe_imports(&data, &[]);
+    let lines: Vec<Vec<String>> = imports.iter().map(|import| import.to_lines(&data)).collect();
+    assert_eq!(
+        lines,
+        vec![
+            vec!["log", "  import: env.log", "  called by: helper"],
+            vec![
+                "put",
+                "  import: l._ (ledger)",
+                "  signature: put_contract_data(k: Val, v: Val, t: StorageType) -> Void",
+                "  called by: helper, main",
+                "  docs: Stores a value. Overwrites any previous one.",
+                "    See also del_contract_data.",
+            ],
+        ]
+    );
+
+    let imports = describe_imports(&data, &["log".to_string(), "missing".to_string()]);
+    assert_eq!(imports.len(), 1);
+    assert_eq!(imports[0].host, None);
+}
@@ -0,0 +1,80 @@
+// Copyright 2025 Stellar Development Foundation and contributors. Licensed
+// under the Apache License, Version 2.0. See the COPYING file at the root
+// of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
+
+use std::collections::HashSet;
+
+use crate::host::{host_info_suffix, HostInfo};
+use crate::parsing::{CallEdge, CallGraphData};
+use crate::vals::format_call_args;
+
+/// Options selecting which annotations are attached to each hop of a chain
+/// or node of a call tree
+#[derive(Debug, Clone, Default)]
+pub struct LabelOptions {
+    /// Suffix functions reached through an indirect call with `?`
+    pub mark_indirect: bool,
+    /// Suffix these functions with `*`: the members of recursion cycles, whose
+    /// chains and trees were cut short where they would call back into themselves
+    pub recursive: Option<HashSet<u32>>,
+    /// Append the kind, code offset and operator index of the call, e.g. `[call@0x2a#5]`
+    pub offsets: bool,
+    /// Append the DWARF source location as `@file:line`: where the function
+    /// starts for the first hop, and where the call was made for later hops
+    pub locations: bool,
+    /// Append the constant arguments of host calls decoded as Soroban values,
+    /// e.g. `(k=Symbol("admin"), t=Persistent)`
+    pub args: bool,
+    /// Append the signature or doc summary of host functions described by env.json
+    pub host_info: Option<HostInfo>,
+}
+
+/// Render a function as it appears in a chain or tree.
+/// `edge` is the call that reached it, or None for the starting function.
+pub fn hop_label(
+    data: &CallGraphData,
+    func_idx: u32,
+    edge: Option<&CallEdge>,
+    opts: &LabelOptions,
+) -> String {
+    let mut label = data.function_name(func_idx);
+
+    if opts.args {
+        if let Some(edge) = edge {
+            label.push_str(&format_call_args(data, edge));
+        }
+    }
+
+    if let (Some(info), Some(host)) = (opts.host_info, data.host_functions.get(&func_idx)) {
+        label.push_str(&host_info_suffix(&host.function, info));
+    }
+
+    if opts.mark_indirect && edge.is_some_and(|e| e.is_indirect()) {
+        label.push('?');
+    }
+
+    if opts.recursive.as_ref().is_some_and(|funcs| funcs.contains(&func_idx)) {
+        label.push('*');
+    }
+
+    if opts.offsets {
+        if let Some(edge) = edge {
+            match edge.site {
+                Some(site) => label.push_str(&format!("[{}@{:#x}#{}]", edge.kind, site.offset, site.op_index)),
+                None => label.push_str(&format!("[{}]", edge.kind)),
+            }
+        }
+    }
+
+    if opts.locations {
+        let location = match edge {
+            Some(edge) => edge.site.and_then(|site| data.call_site_location(&site)),
+            None => data.function_location(func_idx),
+        };
+        if let Some(location) = location {
+            label.push_str(&format!("@{}", location));
+        }
+    }
+
+    label
+}
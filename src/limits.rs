@@ -0,0 +1,62 @@
+// Copyright 2025 Stellar Development Foundation and contributors. Licensed
+// under the Apache License, Version 2.0. See the COPYING file at the root
+// of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
+
+/// Marker shown in place of calls that a limit stopped us from following
+pub const TRUNCATION_MARKER: &str = "...";
+
+/// Bounds on how much of the call graph a traversal explores
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Limits {
+    /// Maximum number of functions in a chain, or levels in a tree, counting the start
+    pub max_depth: Option<usize>,
+    /// Maximum number of chains (in paths mode: tree nodes) produced per module
+    pub max_chains: Option<usize>,
+}
+
+impl Limits {
+    /// Check whether a chain or tree branch this long may not be extended further
+    pub fn at_max_depth(&self, depth: usize) -> bool {
+        self.max_depth.is_some_and(|max| depth >= max)
+    }
+
+    /// Check whether `count` chains or nodes exhaust the budget
+    pub fn at_max_chains(&self, count: usize) -> bool {
+        self.max_chains.is_some_and(|max| count >= max)
+    }
+}
+
+/// What a traversal left out because of its `Limits`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Truncation {
+    /// Calls not followed because their chain or branch was already `max_depth` long
+    pub depth_cutoffs: usize,
+    /// Whether output stopped because `max_chains` was reached
+    pub chain_limit_reached: bool,
+    /// Starting functions not explored at all after `max_chains` was reached
+    pub skipped_starts: usize,
+}
+
+impl Truncation {
+    /// Describe what was skipped, for a warning on stderr. `unit` names what
+    /// `max_chains` counted ("chains" or "tree nodes").
+    pub fn warnings(&self, limits: &Limits, unit: &str) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if self.depth_cutoffs > 0 {
+            warnings.push(format!(
+                "{} calls not followed beyond depth {}",
+                self.depth_cutoffs,
+                limits.max_depth.unwrap_or_default()
+            ));
+        }
+        if self.chain_limit_reached {
+            warnings.push(format!(
+                "output stopped at {} {}; {} starting functions not explored",
+                limits.max_chains.unwrap_or_default(),
+                unit,
+                self.skipped_starts
+            ));
+        }
+        warnings
+    }
+}
@@ -2,18 +2,69 @@
 // under the Apache License, Version 2.0. See the COPYING file at the root
 // of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
 
+mod audit;
+mod baseline;
+mod callers;
+mod capabilities;
 mod chains;
+mod counting;
+mod dead_code;
+mod diagrams;
+mod diff;
+mod dot;
+mod dwarf;
+mod folded;
+mod host;
+mod labels;
+mod limits;
 mod parsing;
 mod paths;
+mod policy;
+mod protocol;
+mod records;
+mod scc;
+mod shortest;
+mod traps;
+mod vals;
 
+use std::collections::{BTreeSet, HashMap};
 use std::fs;
+use std::io::{self, BufWriter, Write};
+use std::ops::ControlFlow;
 use std::path::Path;
 
-use clap::Parser;
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::Serialize;
 
-use chains::enumerate_call_chains;
-use parsing::{apply_implicit_calls, build_env_symbol_map, parse_implicit_calls, parse_wasm_module};
-use paths::generate_call_paths;
+use audit::audit_exports;
+use baseline::{compare_chain_sets, edge_set, format_chain_set, read_chain_set};
+use callers::{generate_caller_trees, walk_caller_chains};
+use capabilities::capability_matrix;
+use chains::{enumerate_call_chains, render_chain, walk_call_chains, Chain, ChainOptions};
+use counting::{count_call_chains, CountOptions};
+use dead_code::find_dead_code;
+use diagrams::{mermaid_flowchart, sequence_diagram, SequenceSyntax};
+use diff::{diff_modules, summarize_module};
+use dot::{reachable_subgraph, render_dot, tree_subgraph};
+use dwarf::read_line_table;
+use folded::{StackCost, StackFolder};
+use host::{describe_imports, HostInfo};
+use labels::LabelOptions;
+use limits::{Limits, Truncation};
+use parsing::{
+    apply_env_functions, apply_implicit_calls, load_env_config, parse_implicit_calls, parse_wasm_module,
+    remove_indirect_calls, stop_at_traps, CallGraphData, RootKind,
+};
+use paths::{generate_call_paths, generate_call_trees, PathOptions};
+use policy::{check_policy, load_policy};
+use protocol::unsupported_imports;
+use records::{
+    AuditRecord, CapabilitiesRecord, ChainRecord, CountRecord, CycleRecord, DeadFunctionRecord, DiffRecord,
+    ImportRecord, RuleRecord, TrapChainRecord, TreeRecord, UnsupportedImportRecord,
+};
+use scc::{find_cycles, recursive_functions};
+use shortest::shortest_call_chains;
+use traps::{render_trap_chain, walk_trap_chains};
 
 /// A tool to analyze WebAssembly module call graphs and enumerate call chains.
 #[derive(Parser, Debug)]
@@ -22,7 +73,11 @@ use paths::generate_call_paths;
 #[command(long_about = "Parses one or more WebAssembly bytecode modules, builds a static call graph,\n\
     and outputs all possible call chains (with recursion inhibition).\n\
     Each line of output shows a comma-separated list of function names in the call chain.")]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// WebAssembly file(s) to analyze
     #[arg(required = true)]
     files: Vec<String>,
@@ -47,15 +102,545 @@ struct Args {
     #[arg(long)]
     leaves_only: bool,
 
+    /// Only start chains and paths from functions that can be entered from outside the
+    /// module, of these kinds (comma-separated or repeated)
+    #[arg(long, value_enum, value_delimiter = ',')]
+    roots: Vec<RootSelector>,
+
     /// Output sequential call summaries in format X{A{C,D},B} instead of call chains.
     /// Optionally provide a pattern separated by .. to filter output (e.g., --paths=X..C..B)
-    #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "")]
+    #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "", conflicts_with = "unsorted")]
     paths: Option<String>,
 
+    /// List defined functions and imports that no export, start function, table entry or
+    /// ref.func target can reach, with their body sizes, instead of call chains
+    #[arg(long, conflicts_with_all = ["paths", "leaves_only", "src", "dst", "roots"])]
+    dead_code: bool,
+
+    /// Print how many chains run from each export (or --src function) to each import
+    /// (or --dst function) as SRC,DST,COUNT, without enumerating them. Counts through
+    /// mutually recursive functions are lower bounds, shown as >=COUNT
+    #[arg(long, conflicts_with_all = ["paths", "dead_code", "leaves_only", "max_depth", "max_chains", "unsorted"])]
+    count: bool,
+
+    /// List each group of mutually recursive functions (and each function that calls
+    /// itself) as MEMBERS: CYCLE, with one example cycle, instead of call chains
+    #[arg(long, conflicts_with_all = ["paths", "dead_code", "count", "leaves_only", "src", "dst"])]
+    cycles: bool,
+
+    /// Only print the K shortest chains (default 1) from each starting function to each
+    /// import, or to each --dst function, shortest first
+    #[arg(
+        long,
+        value_name = "K",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "1",
+        value_parser = clap::value_parser!(u64).range(1..),
+        conflicts_with_all = ["paths", "dead_code", "count", "cycles", "max_depth", "max_chains", "unsorted"]
+    )]
+    shortest: Option<u64>,
+
+    /// Search backwards from each --dst function through its callers, which is much
+    /// faster than searching forwards from every function. Chains are the same; with
+    /// --paths, prints a tree of callers rooted at each destination (X{A,B}: A and B call X)
+    #[arg(long, requires = "dst", conflicts_with_all = ["dead_code", "count", "cycles", "shortest"])]
+    callers: bool,
+
+    /// Check the named rules of a JSON policy file against each module and print
+    /// PASS or FAIL for each, with an example chain per violation; exits with
+    /// code 3 if any rule fails
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = [
+            "paths", "dead_code", "count", "cycles", "shortest", "callers", "src", "dst", "leaves_only",
+            "max_depth", "max_chains", "unsorted"
+        ]
+    )]
+    policy: Option<String>,
+
+    /// Audit each export (or --src export) of a Soroban contract: report which of storage
+    /// writes, token transfers and cross-contract calls it can reach, and whether
+    /// require_auth or require_auth_for_args is called first on every chain. Exports
+    /// that can mutate without an auth check are flagged, and the exit code is 3.
+    /// Needs --env-symbols
+    #[arg(
+        long,
+        requires = "env_symbols",
+        conflicts_with_all = [
+            "paths", "dead_code", "count", "cycles", "shortest", "callers", "policy", "dst", "leaves_only",
+            "max_depth", "max_chains", "unsorted"
+        ]
+    )]
+    audit: bool,
+
+    /// Check that the module can run on protocol version N: list every import whose
+    /// env.json min_supported_protocol is above N or max_supported_protocol below N,
+    /// with the exports that reach it; exits with code 3 if there are any. Needs
+    /// --env-symbols
+    #[arg(
+        long,
+        value_name = "N",
+        requires = "env_symbols",
+        conflicts_with_all = [
+            "paths", "dead_code", "count", "cycles", "shortest", "callers", "policy", "audit", "src", "dst",
+            "leaves_only", "max_depth", "max_chains", "unsorted"
+        ]
+    )]
+    protocol: Option<u32>,
+
+    /// Print a matrix of the host modules and host functions each export (or --src
+    /// export) can reach, as a text table, CSV (--format csv) or one JSON record per
+    /// export. Modules are named from --env-symbols when given
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "paths", "dead_code", "count", "cycles", "shortest", "callers", "policy", "audit", "protocol", "dst",
+            "leaves_only", "max_depth", "max_chains", "unsorted"
+        ]
+    )]
+    capabilities: bool,
+
+    /// List the chains from each export (or --src function) that can end in a trap: in a
+    /// function containing an unreachable instruction, a panic helper, or the
+    /// fail_with_error host function, as CHAIN (KIND). Panic helpers are not followed
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "paths", "dead_code", "count", "cycles", "shortest", "callers", "policy", "audit", "capabilities",
+            "protocol", "dst", "unsorted"
+        ]
+    )]
+    traps: bool,
+
+    /// Save the chains the filters select (or the calls between the functions on them,
+    /// with --baseline-set edges) to FILE, one per line, instead of printing them
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = ["paths", "dead_code", "count", "cycles", "shortest", "policy", "audit", "protocol", "unsorted"]
+    )]
+    save_baseline: Option<String>,
+
+    /// Compare the chains (or calls) the filters select with a file saved by
+    /// --save-baseline, print the ones it doesn't have, and exit with code 3 if there
+    /// are any
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = [
+            "save_baseline", "paths", "dead_code", "count", "cycles", "shortest", "policy", "audit", "protocol", "unsorted"
+        ]
+    )]
+    baseline: Option<String>,
+
+    /// Chains (or calls) in FILE, one per line, are accepted and never reported as new
+    /// by --baseline
+    #[arg(long, value_name = "FILE", requires = "baseline")]
+    allow: Option<String>,
+
+    /// What --save-baseline saves and --baseline compares
+    #[arg(long, value_enum, default_value_t = BaselineSet::Chains)]
+    baseline_set: BaselineSet,
+
+    /// Don't follow calls beyond this many functions per chain (levels per tree in
+    /// paths mode); cut chains and branches end in '...'
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    max_depth: Option<u64>,
+
+    /// Stop after this many chains per file (tree nodes in paths mode); unexpanded
+    /// calls in paths mode become '...'
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    max_chains: Option<u64>,
+
+    /// Print chains as they are found, in depth-first order, instead of sorting them.
+    /// Output starts right away and chains aren't held in memory, which matters when
+    /// there are too many of them to sort
+    #[arg(long)]
+    unsorted: bool,
+
+    /// Output format: text, JSON records carrying the filename, function indices,
+    /// names and import/export flags (json: one array; jsonl: one record per line),
+    /// a Graphviz graph of the calls the filters cover (dot), chains as folded stacks
+    /// for flamegraph tools (folded), or in paths mode a diagram per tree (mermaid
+    /// flowchart, mermaid-sequence or plantuml sequence)
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+
+    /// What the count of each folded stack measures with --format folded
+    #[arg(long, value_enum, default_value_t = CostSelector::Calls)]
+    stack_cost: CostSelector,
+
+    /// Print nothing and stop at the first match; only the exit code is reported
+    #[arg(long, short = 'q')]
+    quiet: bool,
+
     /// Add an implicit edge from an import to an export (host callback).
     /// Format: IMPORT:EXPORT (can be specified multiple times)
     #[arg(long)]
     implicit_call: Vec<String>,
+
+    /// Ignore call_indirect edges resolved through tables, keeping only direct calls
+    #[arg(long)]
+    no_indirect: bool,
+
+    /// Suffix functions reached through an indirect call with '?'
+    #[arg(long)]
+    mark_indirect: bool,
+
+    /// Suffix functions that are part of a recursion cycle with '*'; chains and trees
+    /// stop where they would call back into them
+    #[arg(long)]
+    mark_recursive: bool,
+
+    /// Show the call instruction behind each hop as [KIND@OFFSET#INDEX], where OFFSET
+    /// is relative to the code section and INDEX counts operators in the caller's body
+    #[arg(long)]
+    offsets: bool,
+
+    /// Annotate each hop with its source location as @FILE:LINE, read from the module's
+    /// DWARF line tables (the first hop shows where the function starts, later hops
+    /// show where the call was made)
+    #[arg(long)]
+    locations: bool,
+
+    /// Show the constant arguments of host function calls decoded as Soroban values,
+    /// e.g. put_contract_data(k=Symbol("admin"), t=Persistent). Argument names and
+    /// types come from env.json, so this needs --env-symbols
+    #[arg(long, requires = "env_symbols")]
+    args: bool,
+
+    /// Follow host functions with their signature (argument names and types, and
+    /// result type) or the first sentence of their docs, from env.json. Needs
+    /// --env-symbols
+    #[arg(long, value_enum, requires = "env_symbols")]
+    host_info: Option<HostInfoSelector>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Compare an old and a new build of a module
+    ///
+    /// Prints the imports, functions, calls, chains from exports and call tree nodes
+    /// under exports that were removed (-) or added (+). Functions are matched by name,
+    /// or by index when they have none.
+    Diff(DiffArgs),
+    /// Describe the imports of a module
+    ///
+    /// Prints each import with the module and field it is imported as, the functions
+    /// calling it, and with --env-symbols its signature, the protocol versions it is
+    /// available in and its docs.
+    DescribeImport(DescribeImportArgs),
+}
+
+#[derive(clap::Args, Debug)]
+struct DiffArgs {
+    /// The old build
+    old: String,
+
+    /// The new build
+    new: String,
+
+    /// Path to env.json file for translating short import names to long names
+    #[arg(long)]
+    env_symbols: Option<String>,
+
+    /// Add an implicit edge from an import to an export (host callback), in both builds.
+    /// Format: IMPORT:EXPORT (can be specified multiple times)
+    #[arg(long)]
+    implicit_call: Vec<String>,
+
+    /// Ignore call_indirect edges resolved through tables, keeping only direct calls
+    #[arg(long)]
+    no_indirect: bool,
+
+    /// Don't follow calls beyond this many functions per chain or levels per tree
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..), default_value_t = 32)]
+    max_depth: u64,
+
+    /// Stop after this many chains (and tree nodes) per build
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..), default_value_t = 10_000)]
+    max_chains: u64,
+
+    /// Output format: text lines, or JSON records (json or jsonl) with the change, the
+    /// kind, the value and for tree nodes the node and its subtree
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+}
+
+#[derive(clap::Args, Debug)]
+struct DescribeImportArgs {
+    /// The module
+    file: String,
+
+    /// Imports to describe, by name (all of them if none)
+    names: Vec<String>,
+
+    /// Path to env.json file for translating short import names to long names and
+    /// describing them
+    #[arg(long)]
+    env_symbols: Option<String>,
+
+    /// Output format: text, or JSON records (json or jsonl) with env.json's args,
+    /// return, docs and protocol range
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+}
+
+/// Kinds of externally invocable functions selectable with --roots
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum RootSelector {
+    /// Exported functions
+    Exports,
+    /// The start function
+    Start,
+    /// Functions placed in tables by element segments
+    Table,
+    /// Functions whose reference is taken with ref.func
+    RefFunc,
+    /// All of the above
+    All,
+}
+
+impl RootSelector {
+    fn kinds(self) -> &'static [RootKind] {
+        match self {
+            RootSelector::Exports => &[RootKind::Export],
+            RootSelector::Start => &[RootKind::Start],
+            RootSelector::Table => &[RootKind::Table],
+            RootSelector::RefFunc => &[RootKind::RefFunc],
+            RootSelector::All => &RootKind::ALL,
+        }
+    }
+}
+
+/// Output formats selectable with --format
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// Comma-separated chains and X{A,B} trees
+    Text,
+    /// A JSON array with one object per result
+    Json,
+    /// One JSON object per line (JSON Lines)
+    Jsonl,
+    /// A Graphviz digraph per file
+    Dot,
+    /// A Mermaid flowchart per tree, fenced for Markdown (paths mode)
+    Mermaid,
+    /// A Mermaid sequence diagram per tree, fenced for Markdown (paths mode)
+    MermaidSequence,
+    /// A PlantUML sequence diagram per tree (paths mode)
+    Plantuml,
+    /// Chains as folded stacks, `a;b;c COUNT`, for flamegraph tools
+    Folded,
+    /// Comma-separated values with a header row (--capabilities)
+    Csv,
+}
+
+impl Format {
+    fn is_diagram(self) -> bool {
+        matches!(self, Format::Mermaid | Format::MermaidSequence | Format::Plantuml)
+    }
+}
+
+/// Stack counts selectable with --stack-cost
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum CostSelector {
+    /// Number of chains folded into the stack, one per combination of call sites
+    Calls,
+    /// Body sizes in bytes of the stack's functions added up, for each of those chains
+    Size,
+}
+
+impl CostSelector {
+    fn cost(self) -> StackCost {
+        match self {
+            CostSelector::Calls => StackCost::Calls,
+            CostSelector::Size => StackCost::Size,
+        }
+    }
+}
+
+/// Host function annotations selectable with --host-info
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum HostInfoSelector {
+    /// Argument names and types and the result type
+    Signature,
+    /// The first sentence of the docs
+    Docs,
+}
+
+impl HostInfoSelector {
+    fn info(self) -> HostInfo {
+        match self {
+            HostInfoSelector::Signature => HostInfo::Signature,
+            HostInfoSelector::Docs => HostInfo::Docs,
+        }
+    }
+}
+
+/// Contents of a baseline selectable with --baseline-set
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum BaselineSet {
+    /// Call chains as printed, so any new way to reach a destination counts
+    Chains,
+    /// Caller,callee pairs on the way to the destinations, which change less often
+    Edges,
+}
+
+impl BaselineSet {
+    fn unit(self) -> &'static str {
+        match self {
+            BaselineSet::Chains => "chains",
+            BaselineSet::Edges => "calls",
+        }
+    }
+}
+
+/// A JSON record tagged with the file it came from
+#[derive(Serialize)]
+struct FileRecord<'a, T> {
+    file: &'a str,
+    #[serde(flatten)]
+    record: T,
+}
+
+/// Writer for results, one per line in text format (with the filename prefix
+/// when enabled) or as JSON records. Once no more output is wanted (quiet mode
+/// matched, or stdout was closed) every write returns `Break` so producers can
+/// stop early.
+struct Output<W: Write> {
+    writer: W,
+    format: Format,
+    show_filename: bool,
+    quiet: bool,
+    count: usize,
+    done: bool,
+    error: Option<io::Error>,
+}
+
+impl<W: Write> Output<W> {
+    fn new(writer: W, format: Format, show_filename: bool, quiet: bool) -> Self {
+        Output { writer, format, show_filename, quiet, count: 0, done: false, error: None }
+    }
+
+    /// Count a result and write it with `write`, unless no more output is wanted
+    fn write<F: FnOnce(&mut W) -> io::Result<()>>(&mut self, write: F) -> ControlFlow<()> {
+        if self.done {
+            return ControlFlow::Break(());
+        }
+        self.count += 1;
+        if self.quiet {
+            self.done = true;
+            return ControlFlow::Break(());
+        }
+
+        match write(&mut self.writer) {
+            Ok(()) => ControlFlow::Continue(()),
+            Err(e) => {
+                // A closed pipe (e.g. `| head`) just means nobody wants more output
+                if e.kind() != io::ErrorKind::BrokenPipe {
+                    self.error = Some(e);
+                }
+                self.done = true;
+                ControlFlow::Break(())
+            }
+        }
+    }
+
+    fn line(&mut self, filename: &str, line: &str) -> ControlFlow<()> {
+        let show_filename = self.show_filename;
+        self.write(|w| {
+            if show_filename {
+                writeln!(w, "{}:{}", filename, line)
+            } else {
+                writeln!(w, "{}", line)
+            }
+        })
+    }
+
+    fn lines<I: IntoIterator<Item = String>>(&mut self, filename: &str, lines: I) {
+        for line in lines {
+            if self.line(filename, &line).is_break() {
+                break;
+            }
+        }
+    }
+
+    fn record<T: Serialize>(&mut self, filename: &str, record: T) -> ControlFlow<()> {
+        let separator: &[u8] = match (self.format, self.count) {
+            (Format::Json, 0) => b"[\n",
+            (Format::Json, _) => b",\n",
+            _ => b"",
+        };
+        let terminator: &[u8] = if self.format == Format::Jsonl { b"\n" } else { b"" };
+        self.write(|w| {
+            w.write_all(separator)?;
+            serde_json::to_writer(&mut *w, &FileRecord { file: filename, record })?;
+            w.write_all(terminator)
+        })
+    }
+
+    /// Write a multi-line document as one result, without the filename prefix
+    fn block(&mut self, text: &str) {
+        let _ = self.write(|w| w.write_all(text.as_bytes()));
+    }
+
+    /// Write one result as text or as a JSON record, building only the one needed
+    fn emit<T: Serialize>(
+        &mut self,
+        filename: &str,
+        text: impl FnOnce() -> String,
+        record: impl FnOnce() -> T,
+    ) -> ControlFlow<()> {
+        match self.format {
+            Format::Json | Format::Jsonl => self.record(filename, record()),
+            _ => self.line(filename, &text()),
+        }
+    }
+
+    fn emit_all<I: IntoIterator, T: Serialize>(
+        &mut self,
+        filename: &str,
+        items: I,
+        text: impl Fn(&I::Item) -> String,
+        record: impl Fn(&I::Item) -> T,
+    ) {
+        for item in items {
+            if self.emit(filename, || text(&item), || record(&item)).is_break() {
+                break;
+            }
+        }
+    }
+
+    fn finish(mut self) -> io::Result<usize> {
+        if let Some(e) = self.error.take() {
+            return Err(e);
+        }
+        if self.format == Format::Json && !self.quiet && !self.done {
+            let closing: &[u8] = if self.count == 0 { b"[]\n" } else { b"\n]\n" };
+            if let Err(e) = self.writer.write_all(closing) {
+                if e.kind() != io::ErrorKind::BrokenPipe {
+                    return Err(e);
+                }
+            }
+        }
+        match self.writer.flush() {
+            Err(e) if e.kind() != io::ErrorKind::BrokenPipe => Err(e),
+            _ => Ok(self.count),
+        }
+    }
+}
+
+/// Report on stderr what --max-depth and --max-chains left out
+fn warn_truncation(filename: &str, truncation: &Truncation, limits: &Limits, unit: &str, quiet: bool) {
+    if quiet {
+        return;
+    }
+    for warning in truncation.warnings(limits, unit) {
+        eprintln!("warning: {}: {}", filename, warning);
+    }
 }
 
 fn parse_bool_arg(s: &str) -> Result<bool, String> {
@@ -66,25 +651,146 @@ fn parse_bool_arg(s: &str) -> Result<bool, String> {
     }
 }
 
+/// Read and parse a module, then apply --implicit-call and --no-indirect, and
+/// read its DWARF line information for --locations
+fn load_module(
+    file_path: &str,
+    env_symbol_map: Option<&HashMap<String, String>>,
+    implicit_calls: &HashMap<String, String>,
+    no_indirect: bool,
+    locations: bool,
+) -> Result<CallGraphData, Box<dyn std::error::Error>> {
+    let wasm_bytes = fs::read(file_path)?;
+    let mut data = parse_wasm_module(&wasm_bytes, env_symbol_map)?;
+    if locations {
+        // Broken debug info only costs the locations, not the whole run
+        match read_line_table(&wasm_bytes) {
+            Ok(line_table) => data.line_table = line_table,
+            Err(e) => eprintln!("warning: {}: can't read DWARF line information: {}", file_path, e),
+        }
+    }
+
+    // Apply implicit calls to add edges from imports to exports
+    if !implicit_calls.is_empty() {
+        apply_implicit_calls(&mut data, implicit_calls);
+    }
+    if no_indirect {
+        remove_indirect_calls(&mut data);
+    }
+    Ok(data)
+}
+
+/// Run the `diff` subcommand
+fn run_diff(args: &DiffArgs) -> Result<(), Box<dyn std::error::Error>> {
+    if !matches!(args.format, Format::Text | Format::Json | Format::Jsonl) {
+        return Err("diff prints changes; use --format text, json or jsonl".into());
+    }
+    let env_symbol_map = match args.env_symbols {
+        Some(ref env_path) => Some(load_env_config(env_path)?.symbol_map()),
+        None => None,
+    };
+    let implicit_calls = parse_implicit_calls(&args.implicit_call).map_err(Box::<dyn std::error::Error>::from)?;
+    let limits = Limits {
+        max_depth: Some(args.max_depth as usize),
+        max_chains: Some(args.max_chains as usize),
+    };
+
+    let mut summaries = Vec::new();
+    for file_path in [&args.old, &args.new] {
+        let data = load_module(file_path, env_symbol_map.as_ref(), &implicit_calls, args.no_indirect, false)?;
+        let (summary, chain_truncation, tree_truncation) = summarize_module(&data, limits);
+        warn_truncation(file_path, &chain_truncation, &limits, "chains", false);
+        warn_truncation(file_path, &tree_truncation, &limits, "tree nodes", false);
+        summaries.push(summary);
+    }
+
+    // Records are tagged with the new build
+    let filename = Path::new(&args.new)
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or(&args.new);
+    let mut out = Output::new(BufWriter::new(io::stdout().lock()), args.format, false, false);
+    out.emit_all(filename, diff_modules(&summaries[0], &summaries[1]), |e| e.to_line(), DiffRecord::new);
+    out.finish()?;
+    Ok(())
+}
+
+/// Run the `describe-import` subcommand
+fn run_describe_import(args: &DescribeImportArgs) -> Result<(), Box<dyn std::error::Error>> {
+    if !matches!(args.format, Format::Text | Format::Json | Format::Jsonl) {
+        return Err("describe-import prints imports; use --format text, json or jsonl".into());
+    }
+    let env_config = match args.env_symbols {
+        Some(ref env_path) => Some(load_env_config(env_path)?),
+        None => None,
+    };
+    let env_symbol_map = env_config.as_ref().map(|config| config.symbol_map());
+    let env_function_map = env_config.as_ref().map(|config| config.function_map()).unwrap_or_default();
+    let mut data = load_module(&args.file, env_symbol_map.as_ref(), &HashMap::new(), false, false)?;
+    apply_env_functions(&mut data, &env_function_map);
+
+    let imports = describe_imports(&data, &args.names);
+    let filename = Path::new(&args.file)
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or(&args.file);
+    let mut out = Output::new(BufWriter::new(io::stdout().lock()), args.format, false, false);
+    out.emit_all(filename, &imports, |i| i.to_lines(&data).join("\n"), |i| ImportRecord::new(&data, i));
+    out.finish()?;
+
+    let missing: Vec<&str> = args
+        .names
+        .iter()
+        .filter(|name| !imports.iter().any(|i| data.function_names.get(&i.func_idx) == Some(*name)))
+        .map(|name| name.as_str())
+        .collect();
+    if !missing.is_empty() {
+        return Err(format!("{}: no import named {}", filename, missing.join(", ")).into());
+    }
+    Ok(())
+}
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args = Args::parse();
+    match &args.command {
+        Some(Command::Diff(diff_args)) => return run_diff(diff_args),
+        Some(Command::DescribeImport(describe_args)) => return run_describe_import(describe_args),
+        None => {}
+    }
 
-    // Load env symbols if provided
-    let env_symbol_map = if let Some(ref env_path) = args.env_symbols {
-        Some(build_env_symbol_map(env_path)?)
-    } else {
-        None
+    // Load env.json if provided, once for the symbols, host functions and module names
+    let env_config = match args.env_symbols {
+        Some(ref env_path) => Some(load_env_config(env_path)?),
+        None => None,
     };
+    let env_symbol_map = env_config.as_ref().map(|config| config.symbol_map());
+
+    let policy = match args.policy {
+        Some(ref policy_path) => Some(load_policy(policy_path)?),
+        None => None,
+    };
+
+    let baseline = match args.baseline {
+        Some(ref baseline_path) => Some(read_chain_set(baseline_path)?),
+        None => None,
+    };
+    let allowed = match args.allow {
+        Some(ref allow_path) => read_chain_set(allow_path)?,
+        None => BTreeSet::new(),
+    };
+
+    let env_module_map = env_config.as_ref().map(|config| config.module_map()).unwrap_or_default();
+    let env_function_map = env_config.as_ref().map(|config| config.function_map()).unwrap_or_default();
 
     // Parse implicit calls
     let implicit_calls = parse_implicit_calls(&args.implicit_call)
-        .map_err(|e| Box::<dyn std::error::Error>::from(e))?;
+        .map_err(Box::<dyn std::error::Error>::from)?;
 
     // Determine whether to show filename prefix
     let show_filename = args.filename.unwrap_or(args.files.len() > 1);
 
-    let mut total_paths = 0;
-    
+    let mut out = Output::new(BufWriter::new(io::stdout().lock()), args.format, show_filename, args.quiet);
+
     // Parse path pattern if --paths was provided with a non-empty value
     // Each element can have alternatives separated by |
     let path_pattern: Option<Vec<Vec<String>>> = match &args.paths {
@@ -97,55 +803,350 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     };
 
     let use_paths_mode = args.paths.is_some();
+    if args.format == Format::Dot && (args.dead_code || args.count || args.cycles || args.shortest.is_some()) {
+        return Err("--format dot draws the call graph; it can't be combined with --dead-code, --count, --cycles or --shortest".into());
+    }
+    if args.format == Format::Folded
+        && (use_paths_mode || args.dead_code || args.count || args.cycles || args.shortest.is_some())
+    {
+        return Err("--format folded writes call chains; it can't be combined with --paths, --dead-code, --count, --cycles or --shortest".into());
+    }
+    if args.format.is_diagram() && !use_paths_mode {
+        return Err("--format mermaid, mermaid-sequence and plantuml draw call trees and need --paths".into());
+    }
+    if matches!(args.format, Format::MermaidSequence | Format::Plantuml) && args.callers {
+        return Err("sequence diagrams can't show trees of callers; use --format mermaid".into());
+    }
+    let report_mode = policy.is_some() || args.audit || args.protocol.is_some();
+    if report_mode && !matches!(args.format, Format::Text | Format::Json | Format::Jsonl) {
+        return Err("--policy, --audit and --protocol print a report; use --format text, json or jsonl".into());
+    }
+    if args.format == Format::Csv && !args.capabilities {
+        return Err("--format csv is only for --capabilities".into());
+    }
+    if args.traps && !matches!(args.format, Format::Text | Format::Json | Format::Jsonl) {
+        return Err("--traps lists chains; use --format text, json or jsonl".into());
+    }
+    if args.capabilities && !matches!(args.format, Format::Text | Format::Csv | Format::Json | Format::Jsonl) {
+        return Err("--capabilities prints a matrix; use --format text, csv, json or jsonl".into());
+    }
+    let baseline_mode = args.save_baseline.is_some() || baseline.is_some();
+    if baseline_mode && args.files.len() > 1 {
+        return Err("--save-baseline and --baseline take a single file".into());
+    }
+    if baseline_mode && args.format != Format::Text {
+        return Err("--save-baseline and --baseline only support --format text".into());
+    }
     let has_filter = !args.src.is_empty() || !args.dst.is_empty() || path_pattern.is_some();
+    let root_kinds: Vec<RootKind> = args.roots.iter().flat_map(|r| r.kinds()).copied().collect();
+    let roots = (!root_kinds.is_empty()).then_some(root_kinds.as_slice());
+    let limits = Limits {
+        max_depth: args.max_depth.map(|d| d as usize),
+        max_chains: args.max_chains.map(|n| n as usize),
+    };
+    // Quiet mode prints nothing, so chains are streamed to stop at the first one
+    let sorted = !args.unsorted && !args.quiet;
+    // Failed policy rules, flagged exports, unsupported imports and new chains, for the exit code
+    let mut violations = 0;
 
     for file_path in &args.files {
-        let wasm_bytes = fs::read(file_path)?;
         let filename = Path::new(file_path)
             .file_name()
             .and_then(|s| s.to_str())
             .unwrap_or(file_path);
 
-        let mut data = parse_wasm_module(&wasm_bytes, env_symbol_map.as_ref())?;
-
-        // Apply implicit calls to add edges from imports to exports
-        if !implicit_calls.is_empty() {
-            apply_implicit_calls(&mut data, &implicit_calls);
+        let mut data = load_module(file_path, env_symbol_map.as_ref(), &implicit_calls, args.no_indirect, args.locations)?;
+        if args.traps {
+            stop_at_traps(&mut data);
         }
+        apply_env_functions(&mut data, &env_function_map);
+
+        let labels = LabelOptions {
+            mark_indirect: args.mark_indirect,
+            recursive: args.mark_recursive.then(|| recursive_functions(&data)),
+            offsets: args.offsets,
+            locations: args.locations,
+            args: args.args,
+            host_info: args.host_info.map(HostInfoSelector::info),
+        };
 
-        if use_paths_mode {
-            let summaries = generate_call_paths(
+        if baseline_mode {
+            let current = match args.baseline_set {
+                BaselineSet::Chains => {
+                    let opts = ChainOptions {
+                        src_filter: &args.src,
+                        dst_filter: &args.dst,
+                        roots,
+                        leaves_only: args.leaves_only,
+                        limits,
+                        labels,
+                    };
+                    let mut chains = BTreeSet::new();
+                    let collect = |chain: Chain| {
+                        chains.insert(render_chain(&data, chain, &opts));
+                        ControlFlow::Continue(())
+                    };
+                    let truncation = if args.callers {
+                        walk_caller_chains(&data, &opts, collect)
+                    } else {
+                        walk_call_chains(&data, &opts, collect)
+                    };
+                    warn_truncation(filename, &truncation, &limits, "chains", args.quiet);
+                    chains
+                }
+                BaselineSet::Edges => {
+                    let starts = (!args.src.is_empty() || roots.is_some()).then(|| data.start_functions(&args.src, roots));
+                    let ends = (!args.dst.is_empty()).then(|| data.end_functions(&args.dst));
+                    edge_set(&data, &reachable_subgraph(&data, starts.as_deref(), ends.as_deref()))
+                }
+            };
+            let unit = args.baseline_set.unit();
+            if let Some(baseline_path) = &args.save_baseline {
+                fs::write(baseline_path, format_chain_set(&current, filename))?;
+                if !args.quiet {
+                    eprintln!("{}: saved {} {} to {}", filename, current.len(), unit, baseline_path);
+                }
+            } else if let Some(baseline) = &baseline {
+                let diff = compare_chain_sets(&current, baseline, &allowed);
+                if !args.quiet && !diff.removed.is_empty() {
+                    eprintln!("{}: {} {} of the baseline no longer appear", filename, diff.removed.len(), unit);
+                }
+                violations += diff.added.len();
+                out.lines(filename, diff.added);
+            }
+        } else if args.traps {
+            let opts = ChainOptions {
+                src_filter: &args.src,
+                roots,
+                leaves_only: args.leaves_only,
+                limits,
+                labels,
+                ..Default::default()
+            };
+            let truncation = walk_trap_chains(&data, &opts, |chain, kind| {
+                out.emit(
+                    filename,
+                    || render_trap_chain(&data, chain, kind, &opts),
+                    || TrapChainRecord::new(&data, chain, kind, opts.leaves_only),
+                )
+            });
+            warn_truncation(filename, &truncation, &limits, "chains", args.quiet);
+        } else if args.capabilities {
+            let matrix = capability_matrix(&data, &env_module_map, &args.src);
+            // Without a matching export there is no column, so print nothing at all
+            // and let the exit code report that --src matched nothing
+            match args.format {
+                _ if matrix.exports.is_empty() => {}
+                Format::Csv => out.lines(filename, matrix.to_csv(&data)),
+                Format::Json | Format::Jsonl => {
+                    for column in 0..matrix.exports.len() {
+                        if out.record(filename, CapabilitiesRecord::new(&data, &matrix, column)).is_break() {
+                            break;
+                        }
+                    }
+                }
+                _ => out.lines(filename, matrix.to_table(&data)),
+            }
+        } else if let Some(policy) = &policy {
+            let results = check_policy(&data, policy);
+            violations += results.iter().map(|r| r.violations.len()).sum::<usize>();
+            // A failed rule takes a line per violation but a single record
+            if args.format == Format::Text {
+                out.lines(filename, results.iter().flat_map(|r| r.to_lines(&data)));
+            } else {
+                for result in &results {
+                    if out.record(filename, RuleRecord::new(&data, result)).is_break() {
+                        break;
+                    }
+                }
+            }
+        } else if args.audit {
+            let audits = audit_exports(&data, &args.src);
+            violations += audits.iter().filter(|a| a.flagged()).count();
+            out.emit_all(filename, &audits, |a| a.to_line(&data), |a| AuditRecord::new(&data, a));
+        } else if let Some(protocol) = args.protocol {
+            let unsupported = unsupported_imports(&data, protocol);
+            violations += unsupported.len();
+            out.emit_all(filename, &unsupported, |u| u.to_line(&data), |u| UnsupportedImportRecord::new(&data, u));
+        } else if args.format == Format::Dot {
+            // Draw what the filters cover: the trees matching a --paths pattern, or
+            // every call on a way from --src/--roots to --dst
+            let graph = match path_pattern.as_deref() {
+                Some(pattern) if use_paths_mode => {
+                    let opts = PathOptions {
+                        src_filter: &args.src,
+                        roots,
+                        path_pattern: Some(pattern),
+                        limits,
+                        labels,
+                    };
+                    let (trees, truncation) = generate_call_trees(&data, &opts);
+                    warn_truncation(filename, &truncation, &limits, "tree nodes", args.quiet);
+                    tree_subgraph(&trees)
+                }
+                _ => {
+                    let starts = (!args.src.is_empty() || roots.is_some()).then(|| data.start_functions(&args.src, roots));
+                    let ends = (!args.dst.is_empty()).then(|| data.end_functions(&args.dst));
+                    reachable_subgraph(&data, starts.as_deref(), ends.as_deref())
+                }
+            };
+            if !graph.is_empty() {
+                out.block(&render_dot(&data, &graph, filename));
+            }
+        } else if args.dead_code {
+            let dead = find_dead_code(&data);
+            let dead_bytes: u32 = dead.iter().filter_map(|f| f.size).sum();
+            let dead_imports = dead.iter().filter(|f| f.is_import()).count();
+            if !args.quiet {
+                eprintln!(
+                    "{}: {} unreachable functions ({} bytes), {} unreachable imports",
+                    filename,
+                    dead.len() - dead_imports,
+                    dead_bytes,
+                    dead_imports
+                );
+            }
+            out.emit_all(filename, &dead, |f| f.to_line(), |f| DeadFunctionRecord::new(&data, f));
+        } else if args.count {
+            let counts = count_call_chains(
                 &data,
-                &args.src,
-                path_pattern.as_deref(),
+                &CountOptions { src_filter: &args.src, dst_filter: &args.dst, roots },
             );
-
-            for summary in &summaries {
-                if show_filename {
-                    println!("{}:{}", filename, summary);
+            out.emit_all(filename, &counts, |c| c.to_line(), |c| CountRecord::new(&data, c));
+        } else if args.cycles {
+            out.emit_all(filename, &find_cycles(&data), |c| c.to_line(&data), |c| CycleRecord::new(&data, c));
+        } else if use_paths_mode {
+            let opts = PathOptions {
+                src_filter: &args.src,
+                roots,
+                path_pattern: path_pattern.as_deref(),
+                limits,
+                labels,
+            };
+            let truncation = if args.callers || args.format != Format::Text {
+                let (mut trees, truncation) = if args.callers {
+                    generate_caller_trees(&data, &args.dst, &opts)
                 } else {
-                    println!("{}", summary);
+                    generate_call_trees(&data, &opts)
+                };
+                if !args.callers {
+                    // Same order as the text summaries
+                    trees.sort_by_cached_key(|tree| tree.render(&data, &opts.labels));
                 }
-            }
-            total_paths += summaries.len();
+                match args.format {
+                    Format::Mermaid | Format::MermaidSequence | Format::Plantuml => {
+                        for tree in &trees {
+                            let diagram = match args.format {
+                                Format::Mermaid => mermaid_flowchart(&data, tree, &opts.labels, args.callers),
+                                Format::MermaidSequence => {
+                                    sequence_diagram(&data, tree, &opts.labels, SequenceSyntax::Mermaid)
+                                }
+                                _ => sequence_diagram(&data, tree, &opts.labels, SequenceSyntax::PlantUml),
+                            };
+                            out.block(&diagram);
+                            if out.done {
+                                break;
+                            }
+                        }
+                    }
+                    _ => out.emit_all(
+                        filename,
+                        &trees,
+                        |tree| tree.render(&data, &opts.labels),
+                        |tree| TreeRecord::new(&data, tree),
+                    ),
+                }
+                truncation
+            } else {
+                let (summaries, truncation) = generate_call_paths(&data, &opts);
+                out.lines(filename, summaries);
+                truncation
+            };
+            warn_truncation(filename, &truncation, &limits, "tree nodes", args.quiet);
         } else {
-            let chains = enumerate_call_chains(&data, &args.src, &args.dst, args.leaves_only);
-
-            for chain in &chains {
-                if show_filename {
-                    println!("{}:{}", filename, chain);
+            let opts = ChainOptions {
+                src_filter: &args.src,
+                dst_filter: &args.dst,
+                roots,
+                leaves_only: args.leaves_only,
+                limits,
+                labels,
+            };
+            let chain_record = |chain: Chain| ChainRecord::new(&data, chain, opts.leaves_only);
+            let truncation = if args.format == Format::Folded {
+                // Stacks start where execution does: at exports, unless --src or --roots pick others
+                let roots = match roots {
+                    None if args.src.is_empty() => Some(&[RootKind::Export][..]),
+                    roots => roots,
+                };
+                let opts = ChainOptions { roots, ..opts.clone() };
+                let mut folder = StackFolder::new(args.stack_cost.cost());
+                let fold = |chain: Chain| {
+                    folder.add(&data, chain, &opts);
+                    ControlFlow::Continue(())
+                };
+                let truncation = if args.callers {
+                    walk_caller_chains(&data, &opts, fold)
                 } else {
-                    println!("{}", chain);
-                }
-            }
-            total_paths += chains.len();
+                    walk_call_chains(&data, &opts, fold)
+                };
+                out.lines(filename, folder.into_lines());
+                truncation
+            } else if let Some(k) = args.shortest {
+                let chains = shortest_call_chains(&data, &opts, k as usize);
+                out.emit_all(
+                    filename,
+                    chains.iter().map(|hops| Chain { hops, truncated: false, truncated_start: false }),
+                    |chain| render_chain(&data, *chain, &opts),
+                    |chain| chain_record(*chain),
+                );
+                Truncation::default()
+            } else if sorted && !args.callers && args.format == Format::Text {
+                let (chains, truncation) = enumerate_call_chains(&data, &opts);
+                out.lines(filename, chains);
+                truncation
+            } else if sorted {
+                // Sort by the text form, keeping the JSON record only if it will be printed
+                let mut chains: Vec<(String, Option<ChainRecord>)> = Vec::new();
+                let collect = |chain: Chain| {
+                    let record = (args.format != Format::Text).then(|| chain_record(chain));
+                    chains.push((render_chain(&data, chain, &opts), record));
+                    ControlFlow::Continue(())
+                };
+                let truncation = if args.callers {
+                    walk_caller_chains(&data, &opts, collect)
+                } else {
+                    walk_call_chains(&data, &opts, collect)
+                };
+                chains.sort_by(|a, b| a.0.cmp(&b.0));
+                out.emit_all(filename, chains, |(text, _)| text.clone(), |(_, record)| record.clone());
+                truncation
+            } else if args.callers {
+                walk_caller_chains(&data, &opts, |chain| {
+                    out.emit(filename, || render_chain(&data, chain, &opts), || chain_record(chain))
+                })
+            } else {
+                walk_call_chains(&data, &opts, |chain| {
+                    out.emit(filename, || render_chain(&data, chain, &opts), || chain_record(chain))
+                })
+            };
+            warn_truncation(filename, &truncation, &limits, "chains", args.quiet);
+        }
+
+        // Quiet mode still checks every file for the exit code
+        if out.done && !(args.quiet && report_mode) {
+            break;
         }
     }
+    let total_paths = out.finish()?;
 
     // Exit with code 1 if filters were applied and no paths matched
-    if has_filter && total_paths == 0 {
+    if has_filter && !baseline_mode && total_paths == 0 {
         std::process::exit(1);
     }
+    if violations > 0 {
+        std::process::exit(3);
+    }
 
     Ok(())
 }
@@ -3,22 +3,61 @@
 // of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
 
 use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::fs;
 
-use serde::Deserialize;
-use wasmparser::{ExternalKind, Name, Operator, Payload, TypeRef};
+use serde::{Deserialize, Serialize};
+use wasmparser::{
+    CompositeInnerType, DataKind, ElementItems, ElementKind, ExternalKind, FuncType, Name, Operator, Payload,
+    TypeRef,
+};
+
+use crate::dwarf::{LineTable, SourceLocation};
+
+/// Represents an argument of a function in env.json
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EnvArg {
+    pub name: String,
+    /// Soroban type of the argument, e.g. `Val`, `U32Val` or `StorageType`
+    #[serde(rename = "type")]
+    pub ty: String,
+}
 
 /// Represents a function entry in the env.json module
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
 pub struct EnvFunction {
     pub export: String,
     pub name: String,
+    #[serde(default)]
+    pub args: Vec<EnvArg>,
+    /// Soroban type of the result, e.g. `Void` or `Val`
+    #[serde(rename = "return", default)]
+    pub ret: String,
+    #[serde(default)]
+    pub docs: String,
+    /// First protocol version the function is available in, if not the first one
+    pub min_supported_protocol: Option<u32>,
+    /// Last protocol version the function is available in, if it was removed
+    pub max_supported_protocol: Option<u32>,
+}
+
+/// A host function described by env.json, with the module it belongs to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostFunction {
+    /// Readable module name, e.g. `ledger`
+    pub module: String,
+    /// Short module name the function is imported from, e.g. `l`
+    pub module_export: String,
+    pub function: EnvFunction,
 }
 
 /// Represents a module entry in the env.json file
 #[derive(Debug, Deserialize)]
 pub struct EnvModule {
     pub export: String,
+    /// Readable module name, e.g. `ledger` for export `l`; older env.json files don't have it
+    #[serde(default)]
+    pub name: Option<String>,
     pub functions: Vec<EnvFunction>,
 }
 
@@ -28,19 +67,158 @@ pub struct EnvConfig {
     pub modules: Vec<EnvModule>,
 }
 
-/// Build a lookup map from "module_export.func_export" -> "long_name"
-pub fn build_env_symbol_map(env_path: &str) -> Result<HashMap<String, String>, Box<dyn std::error::Error>> {
+/// Read and parse an env.json file
+pub fn load_env_config(env_path: &str) -> Result<EnvConfig, Box<dyn std::error::Error>> {
     let content = fs::read_to_string(env_path)?;
-    let config: EnvConfig = serde_json::from_str(&content)?;
-    
-    let mut map = HashMap::new();
-    for module in config.modules {
-        for func in module.functions {
-            let key = format!("{}.{}", module.export, func.export);
-            map.insert(key, func.name);
+    Ok(serde_json::from_str(&content)?)
+}
+
+impl EnvConfig {
+    /// Build a lookup map from "module_export.func_export" -> "long_name"
+    pub fn symbol_map(&self) -> HashMap<String, String> {
+        let mut map = HashMap::new();
+        for module in &self.modules {
+            for func in &module.functions {
+                let key = format!("{}.{}", module.export, func.export);
+                map.insert(key, func.name.clone());
+            }
         }
+        map
     }
-    Ok(map)
+
+    /// Build a lookup map from "module_export.func_export" -> host function, e.g. "l._" ->
+    /// put_contract_data with its arguments, return type, docs and protocol range
+    pub fn function_map(&self) -> HashMap<String, HostFunction> {
+        let mut map = HashMap::new();
+        for module in &self.modules {
+            let name = module.name.clone().unwrap_or_else(|| module.export.clone());
+            for function in &module.functions {
+                let key = format!("{}.{}", module.export, function.export);
+                let host = HostFunction { module: name.clone(), module_export: module.export.clone(), function: function.clone() };
+                map.insert(key, host);
+            }
+        }
+        map
+    }
+
+    /// Build a lookup map from "module_export" -> "module_name", e.g. "l" -> "ledger",
+    /// for the modules that have a name
+    pub fn module_map(&self) -> HashMap<String, String> {
+        self.modules
+            .iter()
+            .filter_map(|module| Some((module.export.clone(), module.name.clone()?)))
+            .collect()
+    }
+}
+
+/// The instruction (or mechanism) behind a call edge
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CallKind {
+    Call,
+    ReturnCall,
+    /// A `call_indirect` resolved through a table
+    CallIndirect,
+    /// A `return_call_indirect` resolved through a table
+    ReturnCallIndirect,
+    /// An edge added with `--implicit-call`, with no instruction behind it
+    Implicit,
+}
+
+impl fmt::Display for CallKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            CallKind::Call => "call",
+            CallKind::ReturnCall => "return_call",
+            CallKind::CallIndirect => "call_indirect",
+            CallKind::ReturnCallIndirect => "return_call_indirect",
+            CallKind::Implicit => "implicit",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Location of a call instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CallSite {
+    /// Byte offset of the instruction from the start of the code section contents
+    /// (the same convention DWARF uses for wasm addresses)
+    pub offset: u32,
+    /// Index of the instruction among the operators of the calling function's body
+    pub op_index: u32,
+}
+
+/// A single outgoing edge of the call graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CallEdge {
+    pub callee: u32,
+    pub kind: CallKind,
+    /// Where the call happens; None for implicit edges
+    pub site: Option<CallSite>,
+}
+
+impl CallEdge {
+    pub fn implicit(callee: u32) -> Self {
+        CallEdge { callee, kind: CallKind::Implicit, site: None }
+    }
+
+    pub fn is_indirect(&self) -> bool {
+        matches!(self.kind, CallKind::CallIndirect | CallKind::ReturnCallIndirect)
+    }
+}
+
+/// Paths of Rust's panic helpers, matched as prefixes of demangled function
+/// names so that a hash suffix (`core::panicking::panic_fmt::h1a2b...`) or any
+/// function of a panicking module still matches
+pub const PANIC_HELPER_PATHS: [&str; 5] = [
+    "core::panicking::",
+    "std::panicking::",
+    "core::result::unwrap_failed",
+    "core::option::unwrap_failed",
+    "core::option::expect_failed",
+];
+
+/// The Soroban host function that aborts a contract with an error
+pub const FAIL_WITH_ERROR: &str = "fail_with_error";
+
+/// How a function can end execution with a trap
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrapKind {
+    /// The function contains an `unreachable` instruction
+    Unreachable,
+    /// The function is a panic helper
+    Panic,
+    /// The function is the `fail_with_error` host function
+    FailWithError,
+}
+
+impl fmt::Display for TrapKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            TrapKind::Unreachable => "unreachable",
+            TrapKind::Panic => "panic",
+            TrapKind::FailWithError => "fail_with_error",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Why a function can be invoked from outside the module's own call graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RootKind {
+    /// Exported to the host
+    Export,
+    /// The module's start function
+    Start,
+    /// Placed in a table by an element segment, so reachable from `call_indirect`
+    Table,
+    /// Referenced by `ref.func`, so it may be called through a funcref
+    RefFunc,
+}
+
+impl RootKind {
+    pub const ALL: [RootKind; 4] = [RootKind::Export, RootKind::Start, RootKind::Table, RootKind::RefFunc];
 }
 
 /// Parsed call graph data for a single wasm module
@@ -48,10 +226,118 @@ pub fn build_env_symbol_map(env_path: &str) -> Result<HashMap<String, String>, B
 pub struct CallGraphData {
     pub function_names: HashMap<u32, String>,
     /// Ordered calls with duplicates preserved
-    pub call_graph: HashMap<u32, Vec<u32>>,
+    pub call_graph: HashMap<u32, Vec<CallEdge>>,
     pub all_function_indices: Vec<u32>,
     pub imported_functions: HashSet<u32>,
-    pub exported_functions: HashSet<u32>,
+    /// Module name each function is imported from, e.g. `l` for Soroban's ledger
+    pub import_modules: HashMap<u32, String>,
+    /// Field name each function is imported as, e.g. `_`
+    pub import_fields: HashMap<u32, String>,
+    /// Every function that can be entered from outside, with the reasons why
+    pub roots: HashMap<u32, HashSet<RootKind>>,
+    /// Size in bytes of each defined function's body, including its locals
+    pub function_sizes: HashMap<u32, u32>,
+    /// Code section offset of the first instruction of each defined function
+    pub function_starts: HashMap<u32, u32>,
+    /// DWARF line information; empty unless loaded with `read_line_table`, or if
+    /// the module has no `.debug_line`
+    pub line_table: LineTable,
+    /// Locations of the `unreachable` instructions in each defined function
+    pub trap_sites: HashMap<u32, Vec<CallSite>>,
+    /// Arguments of direct calls that are constants pushed right before the
+    /// call, by call site offset; None for arguments that aren't
+    pub call_args: HashMap<u32, Vec<Option<i64>>>,
+    /// What env.json says about each import it describes
+    pub host_functions: HashMap<u32, HostFunction>,
+    /// Active data segments of memory 0 placed at a constant address, as
+    /// (address, bytes)
+    pub data_segments: Vec<(u32, Vec<u8>)>,
+}
+
+impl CallGraphData {
+    /// The name of a function, or `func_N` if it has none
+    pub fn function_name(&self, func_idx: u32) -> String {
+        self.function_names
+            .get(&func_idx)
+            .cloned()
+            .unwrap_or_else(|| format!("func_{}", func_idx))
+    }
+
+    /// Check whether a function is a root of any of the given kinds
+    pub fn is_root(&self, func_idx: u32, kinds: &[RootKind]) -> bool {
+        self.roots
+            .get(&func_idx)
+            .is_some_and(|found| kinds.iter().any(|k| found.contains(k)))
+    }
+
+    /// Select the defined functions to start a traversal from: all of them, or only
+    /// roots of the given kinds, further restricted to names in `src_filter` if any.
+    pub fn start_functions(&self, src_filter: &[String], roots: Option<&[RootKind]>) -> Vec<u32> {
+        self.all_function_indices
+            .iter()
+            .filter(|&&idx| roots.is_none_or(|kinds| self.is_root(idx, kinds)))
+            .filter(|&&idx| {
+                src_filter.is_empty()
+                    || self
+                        .function_names
+                        .get(&idx)
+                        .is_some_and(|name| src_filter.iter().any(|s| s == name))
+            })
+            .copied()
+            .collect()
+    }
+
+    /// Select the functions a chain may end at: those named in `dst_filter`,
+    /// imported or defined, or all imports if it is empty. Sorted by name.
+    pub fn end_functions(&self, dst_filter: &[String]) -> Vec<u32> {
+        let mut ends: Vec<(String, u32)> = if dst_filter.is_empty() {
+            self.imported_functions.iter().map(|&idx| (self.function_name(idx), idx)).collect()
+        } else {
+            self.imported_functions
+                .iter()
+                .chain(&self.all_function_indices)
+                .map(|&idx| (self.function_name(idx), idx))
+                .filter(|(name, _)| dst_filter.contains(name))
+                .collect()
+        };
+        ends.sort();
+        ends.into_iter().map(|(_, idx)| idx).collect()
+    }
+
+    /// Source location of a defined function, taken from the line of its first instruction
+    pub fn function_location(&self, func_idx: u32) -> Option<SourceLocation<'_>> {
+        let start = *self.function_starts.get(&func_idx)?;
+        self.line_table.lookup(start as u64)
+    }
+
+    /// Source location of a call instruction
+    pub fn call_site_location(&self, site: &CallSite) -> Option<SourceLocation<'_>> {
+        self.line_table.lookup(site.offset as u64)
+    }
+
+    /// The initial contents of `len` bytes of linear memory at `pos`, if a single
+    /// data segment covers them
+    pub fn read_memory(&self, pos: u32, len: u32) -> Option<&[u8]> {
+        self.data_segments.iter().find_map(|(start, bytes)| {
+            let from = pos.checked_sub(*start)? as usize;
+            bytes.get(from..from.checked_add(len as usize)?)
+        })
+    }
+
+    /// How a function can trap: by being `fail_with_error` or a panic helper
+    /// (matched by name), or by containing an `unreachable` instruction
+    pub fn trap_kind(&self, func_idx: u32) -> Option<TrapKind> {
+        let name = self.function_names.get(&func_idx).map_or("", |n| n.as_str());
+        if self.imported_functions.contains(&func_idx) {
+            (name == FAIL_WITH_ERROR).then_some(TrapKind::FailWithError)
+        } else if PANIC_HELPER_PATHS.iter().any(|p| name.starts_with(p)) {
+            Some(TrapKind::Panic)
+        } else if self.trap_sites.contains_key(&func_idx) {
+            Some(TrapKind::Unreachable)
+        } else {
+            None
+        }
+    }
 }
 
 /// Parse a wasm module and extract call graph data
@@ -62,19 +348,46 @@ pub fn parse_wasm_module(
     let mut num_imported_functions: u32 = 0;
     let mut function_names: HashMap<u32, String> = HashMap::new();
     let mut env_translated: HashSet<u32> = HashSet::new(); // Track which names came from env translation
-    let mut call_graph: HashMap<u32, Vec<u32>> = HashMap::new();
+    let mut call_graph: HashMap<u32, Vec<CallEdge>> = HashMap::new();
     let mut current_func_index: u32 = 0;
     let mut all_function_indices: Vec<u32> = Vec::new();
     let mut imported_functions: HashSet<u32> = HashSet::new();
-    let mut exported_functions: HashSet<u32> = HashSet::new();
+    let mut import_modules: HashMap<u32, String> = HashMap::new();
+    let mut import_fields: HashMap<u32, String> = HashMap::new();
+    let mut roots: HashMap<u32, HashSet<RootKind>> = HashMap::new();
+    // Function types indexed by type index; None for non-function (GC) types
+    let mut types: Vec<Option<FuncType>> = Vec::new();
+    // Type index of every function, imports first
+    let mut function_types: Vec<u32> = Vec::new();
+    // Functions placed in each table by active element segments
+    let mut table_functions: HashMap<u32, Vec<u32>> = HashMap::new();
+    // Passive segments can be copied into any table with table.init
+    let mut passive_functions: Vec<u32> = Vec::new();
+    let mut code_section_start: usize = 0;
+    let mut function_sizes: HashMap<u32, u32> = HashMap::new();
+    let mut function_starts: HashMap<u32, u32> = HashMap::new();
+    let mut trap_sites: HashMap<u32, Vec<CallSite>> = HashMap::new();
+    let mut call_args: HashMap<u32, Vec<Option<i64>>> = HashMap::new();
+    let mut data_segments: Vec<(u32, Vec<u8>)> = Vec::new();
 
     for payload in wasmparser::Parser::new(0).parse_all(wasm_bytes) {
         let payload = payload?;
         match payload {
+            Payload::TypeSection(reader) => {
+                for rec_group in reader {
+                    for sub_type in rec_group?.into_types() {
+                        match sub_type.composite_type.inner {
+                            CompositeInnerType::Func(func_type) => types.push(Some(func_type)),
+                            _ => types.push(None),
+                        }
+                    }
+                }
+            }
             Payload::ImportSection(reader) => {
                 for import in reader {
                     let import = import?;
-                    if let TypeRef::Func(_) = import.ty {
+                    if let TypeRef::Func(type_index) = import.ty {
+                        function_types.push(type_index);
                         // Try to translate using env_symbol_map if available
                         let name = if let Some(map) = env_symbol_map {
                             let key = format!("{}.{}", import.module, import.name);
@@ -89,11 +402,79 @@ pub fn parse_wasm_module(
                         };
                         function_names.insert(num_imported_functions, name);
                         imported_functions.insert(num_imported_functions);
+                        import_modules.insert(num_imported_functions, import.module.to_string());
+                        import_fields.insert(num_imported_functions, import.name.to_string());
                         // Note: imports are NOT added to all_function_indices
                         num_imported_functions += 1;
                     }
                 }
             }
+            Payload::FunctionSection(reader) => {
+                for type_index in reader {
+                    function_types.push(type_index?);
+                }
+            }
+            Payload::GlobalSection(reader) => {
+                for global in reader {
+                    let mut ops = global?.init_expr.get_operators_reader();
+                    while !ops.eof() {
+                        if let Operator::RefFunc { function_index } = ops.read()? {
+                            roots.entry(function_index).or_default().insert(RootKind::RefFunc);
+                        }
+                    }
+                }
+            }
+            Payload::StartSection { func, .. } => {
+                roots.entry(func).or_default().insert(RootKind::Start);
+            }
+            Payload::ElementSection(reader) => {
+                for element in reader {
+                    let element = element?;
+                    let mut funcs: Vec<u32> = Vec::new();
+                    match element.items {
+                        ElementItems::Functions(indices) => {
+                            for idx in indices {
+                                funcs.push(idx?);
+                            }
+                        }
+                        ElementItems::Expressions(_, exprs) => {
+                            for expr in exprs {
+                                let mut ops = expr?.get_operators_reader();
+                                while !ops.eof() {
+                                    if let Operator::RefFunc { function_index } = ops.read()? {
+                                        funcs.push(function_index);
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    if !matches!(element.kind, ElementKind::Declared) {
+                        for &idx in &funcs {
+                            roots.entry(idx).or_default().insert(RootKind::Table);
+                        }
+                    }
+                    match element.kind {
+                        ElementKind::Active { table_index, .. } => {
+                            table_functions
+                                .entry(table_index.unwrap_or(0))
+                                .or_default()
+                                .extend(funcs);
+                        }
+                        ElementKind::Passive => passive_functions.extend(funcs),
+                        ElementKind::Declared => {}
+                    }
+                }
+            }
+            Payload::DataSection(reader) => {
+                for data in reader {
+                    let data = data?;
+                    if let DataKind::Active { memory_index: 0, offset_expr } = data.kind {
+                        if let Operator::I32Const { value } = offset_expr.get_operators_reader().read()? {
+                            data_segments.push((value as u32, data.data.to_vec()));
+                        }
+                    }
+                }
+            }
             Payload::ExportSection(reader) => {
                 for export in reader {
                     let export = export?;
@@ -102,45 +483,115 @@ pub fn parse_wasm_module(
                         if !env_translated.contains(&export.index) {
                             function_names.insert(export.index, export.name.to_string());
                         }
-                        exported_functions.insert(export.index);
+                        roots.entry(export.index).or_default().insert(RootKind::Export);
                     }
                 }
             }
-            Payload::CustomSection(reader) => {
-                if reader.name() == "name" {
-                    if let wasmparser::KnownCustom::Name(name_reader) = reader.as_known() {
-                        for name in name_reader {
-                            if let Ok(Name::Function(func_names)) = name {
-                                for naming in func_names {
-                                    if let Ok(naming) = naming {
-                                        // Don't override env-translated names
-                                        if !env_translated.contains(&naming.index) {
-                                            function_names.insert(naming.index, naming.name.to_string());
-                                        }
-                                    }
+            Payload::CustomSection(reader) if reader.name() == "name" => {
+                if let wasmparser::KnownCustom::Name(name_reader) = reader.as_known() {
+                    for name in name_reader {
+                        if let Ok(Name::Function(func_names)) = name {
+                            for naming in func_names.into_iter().flatten() {
+                                // Don't override env-translated names
+                                if !env_translated.contains(&naming.index) {
+                                    function_names.insert(naming.index, naming.name.to_string());
                                 }
                             }
                         }
                     }
                 }
             }
+            Payload::CodeSectionStart { range, .. } => {
+                code_section_start = range.start;
+            }
             Payload::CodeSectionEntry(body) => {
                 let func_index = num_imported_functions + current_func_index;
                 all_function_indices.push(func_index);
-                let mut callees: Vec<u32> = Vec::new();
+                function_sizes.insert(func_index, body.range().len() as u32);
+                let mut callees: Vec<CallEdge> = Vec::new();
 
                 let mut reader = body.get_operators_reader()?;
+                let mut op_index: u32 = 0;
+                // Values pushed since the last instruction that did anything else:
+                // certainly the top of the stack, Some where they are constants
+                let mut pushed: Vec<Option<i64>> = Vec::new();
                 while !reader.eof() {
-                    let op = reader.read()?;
+                    let (op, offset) = reader.read_with_offset()?;
+                    let site = Some(CallSite {
+                        offset: (offset - code_section_start) as u32,
+                        op_index,
+                    });
+                    if op_index == 0 {
+                        function_starts.insert(func_index, (offset - code_section_start) as u32);
+                    }
+                    op_index += 1;
                     match op {
-                        Operator::Call { function_index } => {
-                            callees.push(function_index);
+                        Operator::I32Const { value } => {
+                            pushed.push(Some(value as i64));
+                            continue;
+                        }
+                        Operator::I64Const { value } => {
+                            pushed.push(Some(value));
+                            continue;
                         }
-                        Operator::ReturnCall { function_index } => {
-                            callees.push(function_index);
+                        Operator::LocalGet { .. } | Operator::GlobalGet { .. } => {
+                            pushed.push(None);
+                            continue;
+                        }
+                        Operator::Call { function_index } | Operator::ReturnCall { function_index } => {
+                            let kind = if matches!(op, Operator::Call { .. }) {
+                                CallKind::Call
+                            } else {
+                                CallKind::ReturnCall
+                            };
+                            callees.push(CallEdge { callee: function_index, kind, site });
+
+                            let func_type = function_types
+                                .get(function_index as usize)
+                                .and_then(|&ti| types.get(ti as usize)?.as_ref());
+                            if let (Some(func_type), Some(site)) = (func_type, site) {
+                                let params = func_type.params().len();
+                                let known = pushed.len().min(params);
+                                let mut args = vec![None; params - known];
+                                args.extend_from_slice(&pushed[pushed.len() - known..]);
+                                if args.iter().any(Option::is_some) {
+                                    call_args.insert(site.offset, args);
+                                }
+                                pushed.clear();
+                                pushed.resize(func_type.results().len(), None);
+                                continue;
+                            }
+                        }
+                        Operator::RefFunc { function_index } => {
+                            roots.entry(function_index).or_default().insert(RootKind::RefFunc);
+                        }
+                        Operator::Unreachable => {
+                            trap_sites.entry(func_index).or_default().extend(site);
+                        }
+                        Operator::CallIndirect { type_index, table_index }
+                        | Operator::ReturnCallIndirect { type_index, table_index } => {
+                            let kind = if matches!(op, Operator::CallIndirect { .. }) {
+                                CallKind::CallIndirect
+                            } else {
+                                CallKind::ReturnCallIndirect
+                            };
+                            let targets = table_functions
+                                .get(&table_index)
+                                .into_iter()
+                                .flatten()
+                                .chain(&passive_functions);
+                            let mut seen: HashSet<u32> = HashSet::new();
+                            for &target in targets {
+                                if seen.insert(target)
+                                    && signatures_match(&types, &function_types, target, type_index)
+                                {
+                                    callees.push(CallEdge { callee: target, kind, site });
+                                }
+                            }
                         }
                         _ => {}
                     }
+                    pushed.clear();
                 }
 
                 call_graph.insert(func_index, callees);
@@ -152,9 +603,7 @@ pub fn parse_wasm_module(
 
     // Generate default names for any functions without names
     for &idx in &all_function_indices {
-        if !function_names.contains_key(&idx) {
-            function_names.insert(idx, format!("func_{}", idx));
-        }
+        function_names.entry(idx).or_insert_with(|| format!("func_{}", idx));
     }
 
     Ok(CallGraphData {
@@ -162,10 +611,30 @@ pub fn parse_wasm_module(
         call_graph,
         all_function_indices,
         imported_functions,
-        exported_functions,
+        import_modules,
+        import_fields,
+        roots,
+        function_sizes,
+        function_starts,
+        line_table: LineTable::default(),
+        trap_sites,
+        call_args,
+        host_functions: HashMap::new(),
+        data_segments,
     })
 }
 
+/// Check whether a table entry can be the target of a `call_indirect` with
+/// the given type index. Types are compared structurally, as the runtime does.
+fn signatures_match(types: &[Option<FuncType>], function_types: &[u32], func_idx: u32, type_index: u32) -> bool {
+    let expected = types.get(type_index as usize).and_then(|t| t.as_ref());
+    let actual = function_types
+        .get(func_idx as usize)
+        .and_then(|&t| types.get(t as usize))
+        .and_then(|t| t.as_ref());
+    matches!((expected, actual), (Some(e), Some(a)) if e == a)
+}
+
 /// Parse implicit call arguments and return a map from import name to export name
 pub fn parse_implicit_calls(args: &[String]) -> Result<HashMap<String, String>, String> {
     let mut map = HashMap::new();
@@ -179,6 +648,16 @@ pub fn parse_implicit_calls(args: &[String]) -> Result<HashMap<String, String>,
     Ok(map)
 }
 
+/// Attach env.json descriptions to the imports they describe
+pub fn apply_env_functions(data: &mut CallGraphData, function_map: &HashMap<String, HostFunction>) {
+    for &idx in &data.imported_functions {
+        let key = format!("{}.{}", data.import_modules[&idx], data.import_fields[&idx]);
+        if let Some(host) = function_map.get(&key) {
+            data.host_functions.insert(idx, host.clone());
+        }
+    }
+}
+
 /// Apply implicit calls to the call graph data.
 /// For each import that has an implicit callback to an export, add an edge from the import to the export.
 pub fn apply_implicit_calls(data: &mut CallGraphData, implicit_calls: &HashMap<String, String>) {
@@ -198,11 +677,32 @@ pub fn apply_implicit_calls(data: &mut CallGraphData, implicit_calls: &HashMap<S
             // Add edge from import to export in call_graph
             data.call_graph
                 .entry(imp_idx)
-                .or_insert_with(Vec::new)
-                .push(exp_idx);
+                .or_default()
+                .push(CallEdge::implicit(exp_idx));
         }
     }
 }
 
+/// Drop every edge that was resolved through a table, keeping only direct calls.
+pub fn remove_indirect_calls(data: &mut CallGraphData) {
+    for edges in data.call_graph.values_mut() {
+        edges.retain(|edge| !edge.is_indirect());
+    }
+}
+
+/// Drop the calls made by panic helpers, so that traversals treat them as
+/// leaves like `fail_with_error` instead of walking the formatting code behind them.
+pub fn stop_at_traps(data: &mut CallGraphData) {
+    let helpers: Vec<u32> = data
+        .all_function_indices
+        .iter()
+        .copied()
+        .filter(|&idx| data.trap_kind(idx) == Some(TrapKind::Panic))
+        .collect();
+    for idx in helpers {
+        data.call_graph.insert(idx, Vec::new());
+    }
+}
+
 #[cfg(test)]
 mod tests;
@@ -137,7 +137,7 @@ fn test_implicit_call_basic() {
         .map(|(&idx, _)| idx)
         .unwrap();
 
-    assert!(data.call_graph.get(&host_func_idx).unwrap().contains(&callback_idx));
+    assert!(data.call_graph.get(&host_func_idx).unwrap().contains(&CallEdge::implicit(callback_idx)));
 }
 
 #[test]
@@ -172,5 +172,225 @@ fn test_implicit_call_multiple() {
         .map(|(&idx, _)| idx)
         .unwrap();
 
-    assert!(data.call_graph.get(&host1_idx).unwrap().contains(&cb1_idx));
+    assert!(data.call_graph.get(&host1_idx).unwrap().contains(&CallEdge::implicit(cb1_idx)));
+}
+
+#[test]
+fn test_call_indirect_resolves_matching_table_entries() {
+    let wasm = parse_wat(
+        r#"
+        (module
+            (type $void (func))
+            (type $unary (func (param i32)))
+            (table 3 funcref)
+            (elem (i32.const 0) $a $b $c)
+            (func $main (call_indirect (type $void) (i32.const 0)) (call $a))
+            (func $a)
+            (func $b (param i32))
+            (func $c)
+        )
+        "#,
+    );
+
+    let data = parse_wasm_module(&wasm, None).unwrap();
+
+    // $main is index 0; $a, $b, $c are 1, 2, 3
+    let edges: Vec<(u32, CallKind)> = data.call_graph.get(&0).unwrap().iter().map(|e| (e.callee, e.kind)).collect();
+    assert_eq!(
+        edges,
+        vec![(1, CallKind::CallIndirect), (3, CallKind::CallIndirect), (1, CallKind::Call)]
+    );
+}
+
+#[test]
+fn test_call_indirect_expression_and_passive_segments() {
+    let wasm = parse_wat(
+        r#"
+        (module
+            (type $void (func))
+            (table 1 funcref)
+            (elem (table 0) (i32.const 0) funcref (ref.func $a))
+            (elem funcref (ref.func $b))
+            (elem declare func $c)
+            (func $main (return_call_indirect (type $void) (i32.const 0)))
+            (func $a)
+            (func $b)
+            (func $c)
+        )
+        "#,
+    );
+
+    let data = parse_wasm_module(&wasm, None).unwrap();
+
+    // Active and passive segments can populate the table, declared ones cannot
+    let callees: Vec<u32> = data.call_graph.get(&0).unwrap().iter().map(|e| e.callee).collect();
+    assert_eq!(callees, vec![1, 2]);
+    assert!(data.call_graph.get(&0).unwrap().iter().all(|e| e.is_indirect()));
+}
+
+#[test]
+fn test_remove_indirect_calls() {
+    let wasm = parse_wat(
+        r#"
+        (module
+            (type $void (func))
+            (table funcref (elem $a))
+            (func $main (call_indirect (type $void) (i32.const 0)) (call $b))
+            (func $a)
+            (func $b)
+        )
+        "#,
+    );
+
+    let mut data = parse_wasm_module(&wasm, None).unwrap();
+    assert_eq!(data.call_graph.get(&0).unwrap().len(), 2);
+
+    remove_indirect_calls(&mut data);
+    let edges = data.call_graph.get(&0).unwrap();
+    assert_eq!(edges.len(), 1);
+    assert_eq!((edges[0].callee, edges[0].kind), (2, CallKind::Call));
+}
+
+#[test]
+fn test_call_site_metadata() {
+    let wasm = parse_wat(
+        r#"
+        (module
+            (type $void (func))
+            (table funcref (elem $b))
+            (func $a (nop) (call $b) (nop) (return_call $b))
+            (func $b (call_indirect (type $void) (i32.const 0)))
+        )
+        "#,
+    );
+
+    let data = parse_wasm_module(&wasm, None).unwrap();
+
+    let code_start = wasmparser::Parser::new(0)
+        .parse_all(&wasm)
+        .find_map(|p| match p.unwrap() {
+            Payload::CodeSectionStart { range, .. } => Some(range.start),
+            _ => None,
+        })
+        .unwrap();
+
+    let a_edges = data.call_graph.get(&0).unwrap();
+    assert_eq!(a_edges[0].kind, CallKind::Call);
+    assert_eq!(a_edges[0].site.unwrap().op_index, 1);
+    assert_eq!(a_edges[1].kind, CallKind::ReturnCall);
+    assert_eq!(a_edges[1].site.unwrap().op_index, 3);
+
+    // Offsets point at the call opcodes within the code section
+    assert_eq!(wasm[code_start + a_edges[0].site.unwrap().offset as usize], 0x10);
+    assert_eq!(wasm[code_start + a_edges[1].site.unwrap().offset as usize], 0x12);
+
+    // The i32.const comes first, so call_indirect is operator 1
+    let b_edges = data.call_graph.get(&1).unwrap();
+    assert_eq!(b_edges[0].kind, CallKind::CallIndirect);
+    assert_eq!(b_edges[0].site.unwrap().op_index, 1);
+    assert_eq!(wasm[code_start + b_edges[0].site.unwrap().offset as usize], 0x11);
+}
+
+#[test]
+fn test_implicit_calls_have_no_site() {
+    let wasm = parse_wat(
+        r#"
+        (module
+            (import "env" "host" (func $host))
+            (func $cb (export "cb"))
+        )
+        "#,
+    );
+
+    let mut data = parse_wasm_module(&wasm, None).unwrap();
+    let mut implicit_calls = HashMap::new();
+    implicit_calls.insert("host".to_string(), "cb".to_string());
+    apply_implicit_calls(&mut data, &implicit_calls);
+
+    let edge = data.call_graph.get(&0).unwrap()[0];
+    assert_eq!(edge.kind, CallKind::Implicit);
+    assert!(edge.site.is_none());
+}
+
+#[test]
+fn test_roots() {
+    let wasm = parse_wat(
+        r#"
+        (module
+            (import "env" "host" (func $host))
+            (table 1 funcref)
+            (elem (i32.const 0) $in_table)
+            (global funcref (ref.func $in_global))
+            (start $init)
+            (func $init)
+            (func $main (export "main") (drop (ref.func $in_code)))
+            (func $in_table)
+            (func $in_global)
+            (func $in_code)
+            (func $internal)
+        )
+        "#,
+    );
+
+    let data = parse_wasm_module(&wasm, None).unwrap();
+
+    // $host is 0; $init..$internal are 1..6
+    assert!(data.is_root(1, &[RootKind::Start]));
+    assert!(data.is_root(2, &[RootKind::Export]));
+    assert!(data.is_root(3, &[RootKind::Table]));
+    assert!(data.is_root(4, &[RootKind::RefFunc]));
+    assert!(data.is_root(5, &[RootKind::RefFunc]));
+    assert!(!data.is_root(6, &RootKind::ALL));
+    assert!(!data.is_root(0, &RootKind::ALL));
+    assert!(!data.is_root(2, &[RootKind::Start, RootKind::Table]));
+
+    assert_eq!(data.start_functions(&[], Some(&[RootKind::Export, RootKind::Start])), vec![1, 2]);
+    assert_eq!(data.start_functions(&[], Some(&RootKind::ALL)), vec![1, 2, 3, 4, 5]);
+    assert_eq!(data.start_functions(&["in_table".to_string()], None), vec![3]);
+    assert_eq!(data.start_functions(&[], None).len(), 6);
+}
+
+#[test]
+fn test_env_config_without_module_names() {
+    // Only the exports and long names are needed to translate symbols
+    let config: EnvConfig = serde_json::from_str(
+        r#"{"modules": [{"export": "x", "functions": [{"export": "_", "name": "log_from_linear_memory"}]}]}"#,
+    )
+    .unwrap();
+    assert_eq!(config.modules[0].name, None);
+    assert_eq!(config.symbol_map()["x._"], "log_from_linear_memory");
+    assert!(config.module_map().is_empty());
+    // Host functions fall back to the module's export
+    assert_eq!(config.function_map()["x._"].module, "x");
+}
+
+#[test]
+fn test_env_config_maps() {
+    let config: EnvConfig = serde_json::from_str(
+        r#"{"modules": [{"name": "ledger", "export": "l", "functions": [
+            {"export": "_", "name": "put_contract_data", "args": [{"name": "k", "type": "Val"}], "return": "Void"}
+        ]}]}"#,
+    )
+    .unwrap();
+    assert_eq!(config.symbol_map(), HashMap::from([("l._".to_string(), "put_contract_data".to_string())]));
+    assert_eq!(config.module_map(), HashMap::from([("l".to_string(), "ledger".to_string())]));
+    let put = &config.function_map()["l._"];
+    assert_eq!((put.module.as_str(), put.module_export.as_str()), ("ledger", "l"));
+    assert_eq!(put.function.args[0].ty, "Val");
+}
+
+#[test]
+fn test_function_name() {
+    let wasm = parse_wat(
+        r#"
+        (module
+            (import "env" "log" (func))
+            (func $main (call 0))
+        )
+        "#,
+    );
+    let data = parse_wasm_module(&wasm, None).unwrap();
+    assert_eq!(data.function_name(0), "env:log");
+    assert_eq!(data.function_name(1), "main");
+    assert_eq!(data.function_name(7), "func_7");
 }
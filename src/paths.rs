@@ -3,28 +3,84 @@
 // of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
 
 use std::collections::HashMap;
+use std::fmt;
 
-use crate::parsing::CallGraphData;
+use crate::labels::{hop_label, LabelOptions};
+use crate::limits::{Limits, Truncation, TRUNCATION_MARKER};
+use crate::parsing::{CallEdge, CallGraphData, RootKind};
+
+/// Options controlling call path summaries
+#[derive(Debug, Clone, Default)]
+pub struct PathOptions<'a> {
+    pub src_filter: &'a [String],
+    /// Only start from roots of these kinds (all defined functions if None)
+    pub roots: Option<&'a [RootKind]>,
+    /// Pattern elements, each a list of alternatives, that must appear in order
+    pub path_pattern: Option<&'a [Vec<String>]>,
+    pub limits: Limits,
+    pub labels: LabelOptions,
+}
 
 /// A tree node representing a function call and its children
 #[derive(Debug, Clone)]
 pub struct CallNode {
     pub name: String,
     pub children: Vec<CallNode>,
+    /// Index of the function this node stands for, when built from a module
+    pub func_index: Option<u32>,
+    /// The call that reached this node (None for the root)
+    pub edge: Option<CallEdge>,
+}
+
+impl fmt::Display for CallNode {
+    /// Format the tree as X{A{C,D},B}
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if !self.children.is_empty() {
+            write!(f, "{{")?;
+            for (i, child) in self.children.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ",")?;
+                }
+                write!(f, "{}", child)?;
+            }
+            write!(f, "}}")?;
+        }
+        Ok(())
+    }
 }
 
 impl CallNode {
     pub fn new(name: String) -> Self {
-        CallNode { name, children: Vec::new() }
+        CallNode { name, children: Vec::new(), func_index: None, edge: None }
+    }
+
+    /// A placeholder for calls that a limit stopped us from expanding
+    pub fn truncation_marker() -> Self {
+        CallNode::new(TRUNCATION_MARKER.to_string())
     }
 
-    /// Convert the tree to a string in format X{A{C,D},B}
-    pub fn to_string(&self) -> String {
+    /// Copy this node without its children
+    fn without_children(&self) -> CallNode {
+        CallNode {
+            name: self.name.clone(),
+            children: Vec::new(),
+            func_index: self.func_index,
+            edge: self.edge,
+        }
+    }
+
+    /// Like `to_string`, but with each node labelled according to `opts`
+    pub fn render(&self, data: &CallGraphData, opts: &LabelOptions) -> String {
+        let label = match self.func_index {
+            Some(idx) => hop_label(data, idx, self.edge.as_ref(), opts),
+            None => self.name.clone(),
+        };
         if self.children.is_empty() {
-            self.name.clone()
+            label
         } else {
-            let child_strs: Vec<String> = self.children.iter().map(|c| c.to_string()).collect();
-            format!("{}{{{}}}", self.name, child_strs.join(","))
+            let child_strs: Vec<String> = self.children.iter().map(|c| c.render(data, opts)).collect();
+            format!("{}{{{}}}", label, child_strs.join(","))
         }
     }
 
@@ -62,7 +118,7 @@ impl CallNode {
 
         // If this node matches and pattern is now empty, include just this node
         if matches_current && pattern_after_self.is_empty() {
-            return (Some(CallNode::new(self.name.clone())), pattern_after_self);
+            return (Some(self.without_children()), pattern_after_self);
         }
 
         // Recursively filter children, consuming pattern elements as we go
@@ -79,7 +135,7 @@ impl CallNode {
 
         // Include this node if it matches the current pattern element, or if any child was included
         if matches_current || !filtered_children.is_empty() {
-            let mut node = CallNode::new(self.name.clone());
+            let mut node = self.without_children();
             node.children = filtered_children;
             (Some(node), current_pattern)
         } else {
@@ -91,93 +147,111 @@ impl CallNode {
 /// Generate sequential call summaries in format X{A{C,D},B}
 /// For loops (repeated calls to same function), unroll twice.
 /// Pattern elements can contain alternatives separated by |.
-pub fn generate_call_paths(
-    data: &CallGraphData,
-    src_filter: &[String],
-    path_pattern: Option<&[Vec<String>]>,
-) -> Vec<String> {
-    let mut results = Vec::new();
+/// Also returns what the limits in `opts` cut off.
+pub fn generate_call_paths(data: &CallGraphData, opts: &PathOptions) -> (Vec<String>, Truncation) {
+    let (trees, truncation) = generate_call_trees(data, opts);
+    let mut results: Vec<String> = trees
+        .iter()
+        .map(|tree| tree.render(data, &opts.labels))
+        .collect();
+    results.sort();
+    (results, truncation)
+}
 
+/// State for building the call trees of one module
+struct TreeBuilder<'a> {
+    data: &'a CallGraphData,
+    limits: Limits,
+    visit_counts: HashMap<u32, u32>,
+    /// Nodes built so far, counted against `limits.max_chains`
+    nodes: usize,
+    truncation: Truncation,
+}
+
+impl TreeBuilder<'_> {
     /// Build a call tree for a function, recursively expanding callees.
     /// For loops, we unroll twice by allowing a function to appear at most twice in the path.
-    fn build_call_tree(
-        func_idx: u32,
-        call_graph: &HashMap<u32, Vec<u32>>,
-        function_names: &HashMap<u32, String>,
-        visit_counts: &mut HashMap<u32, u32>,
-    ) -> CallNode {
-        let name = function_names
-            .get(&func_idx)
-            .cloned()
-            .unwrap_or_else(|| format!("func_{}", func_idx));
+    /// Calls beyond the depth limit or node budget become a truncation marker.
+    fn build_call_tree(&mut self, func_idx: u32, edge: Option<CallEdge>, depth: usize) -> CallNode {
+        let mut node = CallNode::new(self.data.function_name(func_idx));
+        node.func_index = Some(func_idx);
+        node.edge = edge;
+        self.nodes += 1;
 
         // Check if we've already visited this function twice (loop unrolling limit)
-        let count = *visit_counts.get(&func_idx).unwrap_or(&0);
+        let count = *self.visit_counts.get(&func_idx).unwrap_or(&0);
         if count >= 2 {
-            return CallNode::new(name);
+            return node;
         }
 
-        // Mark this function as visited
-        *visit_counts.entry(func_idx).or_insert(0) += 1;
+        let callees: &[CallEdge] = self.data.call_graph.get(&func_idx).map_or(&[], |c| c);
+        if !callees.is_empty() && self.limits.at_max_depth(depth) {
+            self.truncation.depth_cutoffs += callees.len();
+            node.children.push(CallNode::truncation_marker());
+            return node;
+        }
 
-        let mut node = CallNode::new(name);
+        // Mark this function as visited
+        *self.visit_counts.entry(func_idx).or_insert(0) += 1;
 
         // Get the ordered calls for this function
-        if let Some(callees) = call_graph.get(&func_idx) {
-            for &callee in callees {
-                let child = build_call_tree(callee, call_graph, function_names, visit_counts);
-                node.children.push(child);
+        for callee in callees {
+            if self.limits.at_max_chains(self.nodes) {
+                self.truncation.chain_limit_reached = true;
+                node.children.push(CallNode::truncation_marker());
+                break;
             }
+            let child = self.build_call_tree(callee.callee, Some(*callee), depth + 1);
+            node.children.push(child);
         }
 
         // Unmark this function (decrement count)
-        if let Some(c) = visit_counts.get_mut(&func_idx) {
+        if let Some(c) = self.visit_counts.get_mut(&func_idx) {
             *c -= 1;
         }
 
         node
     }
+}
+
+/// Build the call tree for every starting function, filtered by the path pattern if any.
+/// Also returns what the limits in `opts` cut off.
+pub fn generate_call_trees(data: &CallGraphData, opts: &PathOptions) -> (Vec<CallNode>, Truncation) {
+    let mut results = Vec::new();
 
     // Determine which functions to start from
-    let start_functions: Vec<u32> = if src_filter.is_empty() {
-        data.all_function_indices.clone()
-    } else {
-        data.all_function_indices
-            .iter()
-            .filter(|&&idx| {
-                data.function_names
-                    .get(&idx)
-                    .map(|name| src_filter.iter().any(|s| s == name))
-                    .unwrap_or(false)
-            })
-            .copied()
-            .collect()
+    let start_functions = data.start_functions(opts.src_filter, opts.roots);
+
+    let mut builder = TreeBuilder {
+        data,
+        limits: opts.limits,
+        visit_counts: HashMap::new(),
+        nodes: 0,
+        truncation: Truncation::default(),
     };
 
-    for func_idx in start_functions {
-        let mut visit_counts: HashMap<u32, u32> = HashMap::new();
-        let tree = build_call_tree(
-            func_idx,
-            &data.call_graph,
-            &data.function_names,
-            &mut visit_counts,
-        );
+    for (i, &func_idx) in start_functions.iter().enumerate() {
+        if builder.limits.at_max_chains(builder.nodes) {
+            builder.truncation.chain_limit_reached = true;
+            builder.truncation.skipped_starts = start_functions.len() - i;
+            break;
+        }
+        let tree = builder.build_call_tree(func_idx, None, 1);
 
         // Check if the tree matches the path pattern
-        if let Some(pattern) = path_pattern {
+        if let Some(pattern) = opts.path_pattern {
             if matches_path_pattern_tree(&tree, pattern) {
                 // Filter the tree to only show matching paths
                 if let Some(filtered) = tree.filter_by_pattern(pattern) {
-                    results.push(filtered.to_string());
+                    results.push(filtered);
                 }
             }
         } else {
-            results.push(tree.to_string());
+            results.push(tree);
         }
     }
 
-    results.sort();
-    results
+    (results, builder.truncation)
 }
 
 /// Check if a call tree matches a path pattern.
@@ -3,13 +3,32 @@
 // of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
 
 use super::*;
-use crate::parsing::{apply_implicit_calls, parse_wasm_module};
+use crate::labels::LabelOptions;
+use crate::limits::Limits;
+use crate::parsing::{apply_implicit_calls, parse_wasm_module, RootKind};
 use std::collections::HashMap;
 
 fn parse_wat(wat_source: &str) -> Vec<u8> {
     wat::parse_str(wat_source).expect("Failed to parse WAT")
 }
 
+/// Shorthand for building summaries with default labels
+fn generate_call_paths(
+    data: &CallGraphData,
+    src_filter: &[String],
+    path_pattern: Option<&[Vec<String>]>,
+) -> Vec<String> {
+    super::generate_call_paths(
+        data,
+        &PathOptions {
+            src_filter,
+            path_pattern,
+            ..Default::default()
+        },
+    )
+    .0
+}
+
 /// Helper to create a pattern from strings. Each string can contain | for alternatives.
 fn pat(elements: &[&str]) -> Vec<Vec<String>> {
     elements.iter()
@@ -479,3 +498,175 @@ fn test_implicit_call_paths_mode_multiple() {
     assert_eq!(paths.len(), 1);
     assert_eq!(paths[0], "main{host1{cb1},host2{cb2}}");
 }
+
+#[test]
+fn test_paths_indirect_calls() {
+    let wasm = parse_wat(
+        r#"
+        (module
+            (type $t (func))
+            (table funcref (elem $a $b))
+            (func $main (call $setup) (call_indirect (type $t) (i32.const 1)))
+            (func $setup)
+            (func $a)
+            (func $b (call $setup))
+        )
+        "#,
+    );
+
+    let data = parse_wasm_module(&wasm, None).unwrap();
+    let paths = generate_call_paths(&data, &["main".to_string()], None);
+
+    // Every table entry with a matching signature is a possible target
+    assert_eq!(paths, vec!["main{setup,a,b{setup}}".to_string()]);
+
+    let opts = PathOptions {
+        src_filter: &["main".to_string()],
+        labels: LabelOptions {
+            mark_indirect: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let (paths, _) = super::generate_call_paths(&data, &opts);
+    assert_eq!(paths, vec!["main{setup,a?,b?{setup}}".to_string()]);
+}
+
+#[test]
+fn test_paths_mark_recursive() {
+    let wasm = parse_wat(
+        r#"
+        (module
+            (import "env" "log" (func $log))
+            (func $main (export "main") (call $walk) (call $log))
+            (func $walk (call $walk) (call $log))
+        )
+        "#,
+    );
+
+    let data = parse_wasm_module(&wasm, None).unwrap();
+    let opts = PathOptions {
+        src_filter: &["main".to_string()],
+        labels: LabelOptions {
+            recursive: Some(crate::scc::recursive_functions(&data)),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let (paths, _) = super::generate_call_paths(&data, &opts);
+    // Loops are unrolled as usual, every visit to the recursive function is marked
+    assert_eq!(paths, vec!["main{walk*{walk*{walk*,log},log},log}".to_string()]);
+}
+
+#[test]
+fn test_paths_offsets_with_implicit_call() {
+    let wasm = parse_wat(
+        r#"
+        (module
+            (import "env" "host_func" (func $host_func))
+            (func $main (export "main") (call $host_func))
+            (func $callback (export "callback"))
+        )
+        "#,
+    );
+
+    let mut data = parse_wasm_module(&wasm, None).unwrap();
+    let mut implicit_calls = HashMap::new();
+    implicit_calls.insert("host_func".to_string(), "callback".to_string());
+    apply_implicit_calls(&mut data, &implicit_calls);
+
+    let offset = data.call_graph.get(&1).unwrap()[0].site.unwrap().offset;
+    let opts = PathOptions {
+        src_filter: &["main".to_string()],
+        labels: LabelOptions {
+            offsets: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let (paths, _) = super::generate_call_paths(&data, &opts);
+
+    // Implicit edges have no instruction, so only their kind is shown
+    assert_eq!(paths, vec![format!("main{{host_func[call@{:#x}#0]{{callback[implicit]}}}}", offset)]);
+}
+
+#[test]
+fn test_paths_roots_filter() {
+    let wasm = parse_wat(
+        r#"
+        (module
+            (start $init)
+            (func $init (call $helper))
+            (func $main (export "main") (call $helper))
+            (func $helper)
+        )
+        "#,
+    );
+
+    let data = parse_wasm_module(&wasm, None).unwrap();
+    let opts = PathOptions {
+        roots: Some(&[RootKind::Start, RootKind::Export]),
+        ..Default::default()
+    };
+    let (paths, _) = super::generate_call_paths(&data, &opts);
+
+    // helper is not externally invocable, so it gets no tree of its own
+    assert_eq!(paths, vec!["init{helper}".to_string(), "main{helper}".to_string()]);
+}
+
+#[test]
+fn test_paths_max_depth() {
+    let wasm = parse_wat(
+        r#"
+        (module
+            (func $a (call $b) (call $d))
+            (func $b (call $c))
+            (func $c)
+            (func $d)
+        )
+        "#,
+    );
+
+    let data = parse_wasm_module(&wasm, None).unwrap();
+    let opts = PathOptions {
+        src_filter: &["a".to_string()],
+        limits: Limits {
+            max_depth: Some(2),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let (paths, truncation) = super::generate_call_paths(&data, &opts);
+
+    assert_eq!(paths, vec!["a{b{...},d}".to_string()]);
+    assert_eq!(truncation.depth_cutoffs, 1);
+}
+
+#[test]
+fn test_paths_max_chains() {
+    let wasm = parse_wat(
+        r#"
+        (module
+            (func $a (call $b) (call $c))
+            (func $b)
+            (func $c)
+            (func $d)
+        )
+        "#,
+    );
+
+    let data = parse_wasm_module(&wasm, None).unwrap();
+    let opts = PathOptions {
+        limits: Limits {
+            max_chains: Some(2),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let (paths, truncation) = super::generate_call_paths(&data, &opts);
+
+    // The budget runs out inside a's tree; no other tree is started
+    assert_eq!(paths, vec!["a{b,...}".to_string()]);
+    assert!(truncation.chain_limit_reached);
+    assert_eq!(truncation.skipped_starts, 3);
+}
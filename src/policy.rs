@@ -0,0 +1,269 @@
+// Copyright 2025 Stellar Development Foundation and contributors. Licensed
+// under the Apache License, Version 2.0. See the COPYING file at the root
+// of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+
+use serde::Deserialize;
+
+use crate::callers::functions_reaching;
+use crate::chains::ChainOptions;
+use crate::parsing::{CallEdge, CallGraphData, RootKind};
+use crate::shortest::shortest_call_chains;
+
+/// Root structure of a policy file
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Policy {
+    pub rules: Vec<Rule>,
+}
+
+/// A named rule, e.g. `{"name": "no-upgrade", "forbid": {"to": ["update_current_contract_wasm"]}}`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Rule {
+    pub name: String,
+    #[serde(flatten)]
+    pub check: Check,
+}
+
+/// What a rule checks. Function names are matched like `--src` and `--dst`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Check {
+    /// No call chain may run from a function in `from` (every export if
+    /// empty) to one in `to`
+    Forbid {
+        #[serde(default)]
+        from: Vec<String>,
+        to: Vec<String>,
+    },
+    /// Every call chain from a function in `from` (every export if empty) to
+    /// one in `to` must call a function in `before` first
+    RequireBefore {
+        #[serde(default)]
+        from: Vec<String>,
+        to: Vec<String>,
+        before: Vec<String>,
+    },
+    /// In each function of `in`, a call that can reach `then` must come after
+    /// a call that can reach `first`
+    Order {
+        #[serde(rename = "in")]
+        functions: Vec<String>,
+        first: Vec<String>,
+        then: Vec<String>,
+    },
+}
+
+impl Check {
+    /// The rule kind as written in the policy file
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Check::Forbid { .. } => "forbid",
+            Check::RequireBefore { .. } => "require_before",
+            Check::Order { .. } => "order",
+        }
+    }
+
+    /// The name lists that must not be empty, with their keys
+    fn required_lists(&self) -> Vec<(&'static str, &[String])> {
+        match self {
+            Check::Forbid { to, .. } => vec![("to", to)],
+            Check::RequireBefore { to, before, .. } => vec![("to", to), ("before", before)],
+            Check::Order { functions, first, then } => vec![("in", functions), ("first", first), ("then", then)],
+        }
+    }
+}
+
+/// Parse a policy from JSON, checking that every rule names the functions it is about
+pub fn parse_policy(json: &str) -> Result<Policy, Box<dyn std::error::Error>> {
+    let policy: Policy = serde_json::from_str(json)?;
+    for rule in &policy.rules {
+        for (key, names) in rule.check.required_lists() {
+            if names.is_empty() {
+                return Err(format!("policy rule '{}': '{}' must name at least one function", rule.name, key).into());
+            }
+        }
+    }
+    Ok(policy)
+}
+
+/// Read and parse a policy file
+pub fn load_policy(path: &str) -> Result<Policy, Box<dyn std::error::Error>> {
+    let content = fs::read_to_string(path)?;
+    parse_policy(&content)
+}
+
+/// The outcome of one rule on one module
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleResult<'a> {
+    pub rule: &'a Rule,
+    /// Example chains breaking the rule, as function indices; empty if it passed
+    pub violations: Vec<Vec<u32>>,
+}
+
+impl RuleResult<'_> {
+    pub fn passed(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Format as `PASS name`, or one `FAIL name: a,b,c` line per violation
+    pub fn to_lines(&self, data: &CallGraphData) -> Vec<String> {
+        if self.passed() {
+            return vec![format!("PASS {}", self.rule.name)];
+        }
+        self.violations
+            .iter()
+            .map(|chain| {
+                let names: Vec<String> = chain.iter().map(|&idx| data.function_name(idx)).collect();
+                format!("FAIL {}: {}", self.rule.name, names.join(","))
+            })
+            .collect()
+    }
+}
+
+/// Check every rule of `policy` against a module, in the order they are written
+pub fn check_policy<'a>(data: &CallGraphData, policy: &'a Policy) -> Vec<RuleResult<'a>> {
+    policy
+        .rules
+        .iter()
+        .map(|rule| {
+            let violations = match &rule.check {
+                Check::Forbid { from, to } => forbidden_chains(data, from, to),
+                Check::RequireBefore { from, to, before } => unguarded_chains(data, from, to, before),
+                Check::Order { functions, first, then } => misordered_calls(data, functions, first, then),
+            };
+            RuleResult { rule, violations }
+        })
+        .collect()
+}
+
+/// Starting functions of a rule: those named in `from`, or every export
+fn rule_starts(data: &CallGraphData, from: &[String]) -> Vec<u32> {
+    if from.is_empty() {
+        data.start_functions(from, Some(&[RootKind::Export]))
+    } else {
+        data.start_functions(from, None)
+    }
+}
+
+/// The shortest chain from each starting function to each forbidden destination
+fn forbidden_chains(data: &CallGraphData, from: &[String], to: &[String]) -> Vec<Vec<u32>> {
+    let opts = ChainOptions {
+        src_filter: from,
+        dst_filter: to,
+        roots: from.is_empty().then_some(&[RootKind::Export][..]),
+        ..Default::default()
+    };
+    shortest_call_chains(data, &opts, 1)
+        .iter()
+        .map(|hops| hops.iter().map(|hop| hop.func_index).collect())
+        .collect()
+}
+
+/// Functions that count as checks for `unguarded_routes`, and every function
+/// that can reach one of them
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Guards {
+    functions: HashSet<u32>,
+    reaching: HashSet<u32>,
+}
+
+impl Guards {
+    pub fn new(data: &CallGraphData, functions: &[u32]) -> Self {
+        Guards { functions: functions.iter().copied().collect(), reaching: functions_reaching(data, functions) }
+    }
+
+    /// The position of the first of a function's calls that can reach a guard,
+    /// or the number of calls if none can
+    pub fn first_guarded(&self, edges: &[CallEdge]) -> usize {
+        edges
+            .iter()
+            .position(|e| self.reaching.contains(&e.callee))
+            .unwrap_or(edges.len())
+    }
+}
+
+/// The shortest chain from `start` to each of `ends` (in that order) that
+/// doesn't call one of the guard functions first.
+///
+/// A chain calls a guard first if it passes through a guard, or if one of its
+/// functions makes a call that can reach a guard before the call the chain
+/// continues with. Whether a call is allowed therefore depends only on the
+/// caller and the call's position, so an unguarded chain exists exactly when
+/// the destination can be reached through allowed calls, which a breadth-first
+/// search finds. Like the rest of the tool this is static: a call that can
+/// reach a guard counts even if the guard is behind a branch.
+pub fn unguarded_routes(data: &CallGraphData, start: u32, ends: &[u32], guards: &Guards) -> Vec<Vec<u32>> {
+    if guards.functions.contains(&start) {
+        return Vec::new();
+    }
+    let mut parent: HashMap<u32, u32> = HashMap::new();
+    let mut visited: HashSet<u32> = HashSet::from([start]);
+    let mut queue = VecDeque::from([start]);
+    while let Some(func_idx) = queue.pop_front() {
+        let Some(edges) = data.call_graph.get(&func_idx) else {
+            continue;
+        };
+        // Calls from the first one that can reach a guard onwards are guarded,
+        // but that call itself may reach the destination before the guard
+        let first_guarded = guards.first_guarded(edges);
+        for edge in &edges[..edges.len().min(first_guarded + 1)] {
+            if guards.functions.contains(&edge.callee) || !visited.insert(edge.callee) {
+                continue;
+            }
+            parent.insert(edge.callee, func_idx);
+            queue.push_back(edge.callee);
+        }
+    }
+
+    let mut routes = Vec::new();
+    for &end in ends {
+        if end == start || !visited.contains(&end) {
+            continue;
+        }
+        let mut chain = vec![end];
+        while let Some(&caller) = parent.get(chain.last().unwrap()) {
+            chain.push(caller);
+        }
+        chain.reverse();
+        routes.push(chain);
+    }
+    routes
+}
+
+/// Unguarded chains from each starting function to each destination
+fn unguarded_chains(data: &CallGraphData, from: &[String], to: &[String], before: &[String]) -> Vec<Vec<u32>> {
+    let guards = Guards::new(data, &data.end_functions(before));
+    let ends = data.end_functions(to);
+    rule_starts(data, from)
+        .into_iter()
+        .flat_map(|start| unguarded_routes(data, start, &ends, &guards))
+        .collect()
+}
+
+/// Calls made by functions in `functions` that can reach one of `then` before
+/// any call that can reach one of `first`, as `[caller, callee]` pairs. The
+/// first call that can reach `first` satisfies the rule even if it can also
+/// reach `then`, since the order inside it is not a matter of sibling calls.
+fn misordered_calls(data: &CallGraphData, functions: &[String], first: &[String], then: &[String]) -> Vec<Vec<u32>> {
+    let reaching_first = functions_reaching(data, &data.end_functions(first));
+    let reaching_then = functions_reaching(data, &data.end_functions(then));
+
+    let mut violations: Vec<Vec<u32>> = Vec::new();
+    for caller in data.end_functions(functions) {
+        for edge in data.call_graph.get(&caller).into_iter().flatten() {
+            if reaching_first.contains(&edge.callee) {
+                break;
+            }
+            let violation = vec![caller, edge.callee];
+            if reaching_then.contains(&edge.callee) && !violations.contains(&violation) {
+                violations.push(violation);
+            }
+        }
+    }
+    violations
+}
+
+#[cfg(test)]
+mod tests;
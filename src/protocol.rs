@@ -0,0 +1,61 @@
+// Copyright 2025 Stellar Development Foundation and contributors. Licensed
+// under the Apache License, Version 2.0. See the COPYING file at the root
+// of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
+
+use crate::callers::functions_reaching;
+use crate::host::protocol_range;
+use crate::parsing::{CallGraphData, EnvFunction, RootKind};
+
+/// Whether a host function is available in a protocol version
+pub fn supports_protocol(function: &EnvFunction, protocol: u32) -> bool {
+    function.min_supported_protocol.is_none_or(|min| min <= protocol)
+        && function.max_supported_protocol.is_none_or(|max| max >= protocol)
+}
+
+/// An import that isn't available in the protocol version being checked
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsupportedImport {
+    pub func_idx: u32,
+    /// The exports whose chains reach it, in function index order
+    pub reached_by: Vec<u32>,
+}
+
+/// Find the imports described in env.json that `protocol` doesn't support,
+/// sorted by name. The module can't be instantiated on that protocol even if
+/// no export reaches them.
+pub fn unsupported_imports(data: &CallGraphData, protocol: u32) -> Vec<UnsupportedImport> {
+    let exports = data.start_functions(&[], Some(&[RootKind::Export]));
+    let mut unsupported: Vec<(&String, UnsupportedImport)> = data
+        .host_functions
+        .iter()
+        .filter(|(_, host)| !supports_protocol(&host.function, protocol))
+        .map(|(&idx, host)| {
+            let reaching = functions_reaching(data, &[idx]);
+            let reached_by = exports.iter().copied().filter(|e| reaching.contains(e)).collect();
+            (&host.function.name, UnsupportedImport { func_idx: idx, reached_by })
+        })
+        .collect();
+    unsupported.sort_by(|a, b| a.0.cmp(b.0).then(a.1.func_idx.cmp(&b.1.func_idx)));
+    unsupported.into_iter().map(|(_, import)| import).collect()
+}
+
+impl UnsupportedImport {
+    /// Format as `UNSUPPORTED name (protocol 22..): reached by a, b`
+    pub fn to_line(&self, data: &CallGraphData) -> String {
+        let range = data
+            .host_functions
+            .get(&self.func_idx)
+            .and_then(|host| protocol_range(&host.function))
+            .unwrap_or_default();
+        let reached_by = if self.reached_by.is_empty() {
+            "not reached by any export".to_string()
+        } else {
+            let exports: Vec<String> = self.reached_by.iter().map(|&idx| data.function_name(idx)).collect();
+            format!("reached by {}", exports.join(", "))
+        };
+        format!("UNSUPPORTED {} (protocol {}): {}", data.function_name(self.func_idx), range, reached_by)
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,73 @@
+// Copyright 2025 Stellar Development Foundation and contributors. Licensed
+// under the Apache License, Version 2.0. See the COPYING file at the root
+// of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
+
+use std::collections::HashMap;
+
+use super::*;
+use crate::parsing::{apply_env_functions, parse_wasm_module, HostFunction};
+
+fn parse_wat(wat_source: &str) -> Vec<u8> {
+    wat::parse_str(wat_source).expect("Failed to parse WAT")
+}
+
+/// A host function of module `b` available from `min` up to `max`
+fn host(export: &str, name: &str, min: Option<u32>, max: Option<u32>) -> (String, HostFunction) {
+    let function: EnvFunction = serde_json::from_value(serde_json::json!({
+        "export": export,
+        "name": name,
+        "min_supported_protocol": min,
+        "max_supported_protocol": max,
+    }))
+    .unwrap();
+    let host = HostFunction { module: "buf".to_string(), module_export: "b".to_string(), function };
+    (format!("b.{}", export), host)
+}
+
+#[test]
+fn test_supports_protocol() {
+    let (_, since_23) = host("n", "string_to_bytes", Some(23), None);
+    assert!(!supports_protocol(&since_23.function, 22));
+    assert!(supports_protocol(&since_23.function, 23));
+
+    let (_, only_19) = host("z", "protocol_gated_dummy", Some(19), Some(19));
+    assert!(supports_protocol(&only_19.function, 19));
+    assert!(!supports_protocol(&only_19.function, 20));
+
+    let (_, always) = host("j", "symbol_new_from_linear_memory", None, None);
+    assert!(supports_protocol(&always.function, 1));
+}
+
+#[test]
+fn test_unsupported_imports() {
+    let wasm = parse_wat(
+        r#"
+        (module
+            (import "b" "n" (func $string_to_bytes))
+            (import "b" "o" (func $bytes_to_string))
+            (import "b" "j" (func $symbol_new))
+            (import "env" "log" (func $log))
+            (func $main (export "main") (call $helper) (call $symbol_new))
+            (func $convert (export "convert") (call $helper))
+            (func $helper (call $string_to_bytes) (call $log))
+        )
+        "#,
+    );
+    let mut data = parse_wasm_module(&wasm, None).unwrap();
+    let functions = HashMap::from([
+        host("n", "string_to_bytes", Some(23), None),
+        host("o", "bytes_to_string", Some(23), None),
+        host("j", "symbol_new_from_linear_memory", None, None),
+    ]);
+    apply_env_functions(&mut data, &functions);
+
+    let lines: Vec<String> = unsupported_imports(&data, 22).iter().map(|u| u.to_line(&data)).collect();
+    assert_eq!(
+        lines,
+        vec![
+            "UNSUPPORTED bytes_to_string (protocol 23..): not reached by any export",
+            "UNSUPPORTED string_to_bytes (protocol 23..): reached by main, convert",
+        ]
+    );
+    assert!(unsupported_imports(&data, 23).is_empty());
+}
@@ -0,0 +1,440 @@
+// Copyright 2025 Stellar Development Foundation and contributors. Licensed
+// under the Apache License, Version 2.0. See the COPYING file at the root
+// of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
+
+use serde::Serialize;
+
+use crate::audit::{Capability, CapabilityUse, ExportAudit};
+use crate::capabilities::CapabilityMatrix;
+use crate::chains::{Chain, Hop};
+use crate::counting::ChainCount;
+use crate::dead_code::DeadFunction;
+use crate::diff::{Change, DiffEntry, DiffKind};
+use crate::host::ImportDescription;
+use crate::parsing::{CallEdge, CallGraphData, CallKind, EnvArg, HostFunction, RootKind, TrapKind};
+use crate::paths::CallNode;
+use crate::policy::RuleResult;
+use crate::protocol::UnsupportedImport;
+use crate::scc::RecursionCycle;
+use crate::vals::call_args;
+
+/// A function as it appears in JSON output
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FunctionRecord {
+    pub index: u32,
+    pub name: String,
+    pub import: bool,
+    pub export: bool,
+}
+
+impl FunctionRecord {
+    pub fn new(data: &CallGraphData, func_idx: u32) -> Self {
+        FunctionRecord {
+            index: func_idx,
+            name: data.function_name(func_idx),
+            import: data.imported_functions.contains(&func_idx),
+            export: data.is_root(func_idx, &[RootKind::Export]),
+        }
+    }
+}
+
+/// The call that reached a function in a chain or tree
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CallRecord {
+    pub kind: CallKind,
+    /// Code section offset of the call instruction; absent for implicit calls
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub op_index: Option<u32>,
+    /// `file:line` of the call, if the module has DWARF line information
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    /// Constant arguments of a call to a host function described by env.json, decoded
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<ArgRecord>,
+}
+
+/// A constant argument of a call, decoded as a Soroban value
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ArgRecord {
+    pub name: String,
+    pub value: String,
+}
+
+impl CallRecord {
+    pub fn new(data: &CallGraphData, edge: &CallEdge) -> Self {
+        CallRecord {
+            kind: edge.kind,
+            offset: edge.site.map(|site| site.offset),
+            op_index: edge.site.map(|site| site.op_index),
+            location: edge
+                .site
+                .and_then(|site| data.call_site_location(&site))
+                .map(|location| location.to_string()),
+            args: call_args(data, edge)
+                .into_iter()
+                .map(|(name, value)| ArgRecord { name, value })
+                .collect(),
+        }
+    }
+}
+
+/// One function of a chain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HopRecord {
+    #[serde(flatten)]
+    pub function: FunctionRecord,
+    /// None for the starting function
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub call: Option<CallRecord>,
+}
+
+impl HopRecord {
+    pub fn new(data: &CallGraphData, hop: &Hop) -> Self {
+        HopRecord {
+            function: FunctionRecord::new(data, hop.func_index),
+            call: hop.edge.as_ref().map(|edge| CallRecord::new(data, edge)),
+        }
+    }
+}
+
+/// A call chain, from its starting function to its last one
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChainRecord {
+    pub chain: Vec<HopRecord>,
+    /// The last function has calls that `--max-depth` kept us from following
+    pub truncated: bool,
+    /// The first function has callers that `--max-depth` kept us from following,
+    /// with `--callers`
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub truncated_start: bool,
+}
+
+impl ChainRecord {
+    /// With `leaves_only`, only the first and last function are kept, as in text output
+    pub fn new(data: &CallGraphData, chain: Chain, leaves_only: bool) -> Self {
+        let hops: Vec<&Hop> = match chain.hops {
+            [first, .., last] if leaves_only => vec![first, last],
+            hops => hops.iter().collect(),
+        };
+        ChainRecord {
+            chain: hops.into_iter().map(|hop| HopRecord::new(data, hop)).collect(),
+            truncated: chain.truncated,
+            truncated_start: chain.truncated_start,
+        }
+    }
+}
+
+/// A node of a call tree (or caller tree)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TreeNodeRecord {
+    /// None for a node standing in for calls a limit kept us from expanding
+    #[serde(flatten)]
+    pub function: Option<FunctionRecord>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub call: Option<CallRecord>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<TreeNodeRecord>,
+}
+
+impl TreeNodeRecord {
+    pub fn new(data: &CallGraphData, node: &CallNode) -> Self {
+        TreeNodeRecord {
+            function: node.func_index.map(|idx| FunctionRecord::new(data, idx)),
+            call: node.edge.as_ref().map(|edge| CallRecord::new(data, edge)),
+            truncated: node.func_index.is_none(),
+            children: node.children.iter().map(|child| TreeNodeRecord::new(data, child)).collect(),
+        }
+    }
+}
+
+/// A whole call tree in paths mode
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TreeRecord {
+    pub tree: TreeNodeRecord,
+}
+
+impl TreeRecord {
+    pub fn new(data: &CallGraphData, tree: &CallNode) -> Self {
+        TreeRecord { tree: TreeNodeRecord::new(data, tree) }
+    }
+}
+
+/// A row of `--count`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CountRecord {
+    pub src: FunctionRecord,
+    pub dst: FunctionRecord,
+    pub count: u128,
+    /// False if `count` is only a lower bound
+    pub exact: bool,
+}
+
+impl CountRecord {
+    pub fn new(data: &CallGraphData, count: &ChainCount) -> Self {
+        CountRecord {
+            src: FunctionRecord::new(data, count.src_index),
+            dst: FunctionRecord::new(data, count.dst_index),
+            count: count.count,
+            exact: count.exact,
+        }
+    }
+}
+
+/// A recursion cycle from `--cycles`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CycleRecord {
+    pub members: Vec<FunctionRecord>,
+    pub example: Vec<FunctionRecord>,
+}
+
+impl CycleRecord {
+    pub fn new(data: &CallGraphData, cycle: &RecursionCycle) -> Self {
+        let functions = |funcs: &[u32]| funcs.iter().map(|&idx| FunctionRecord::new(data, idx)).collect();
+        CycleRecord { members: functions(&cycle.members), example: functions(&cycle.example) }
+    }
+}
+
+/// An unreachable function from `--dead-code`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DeadFunctionRecord {
+    #[serde(flatten)]
+    pub function: FunctionRecord,
+    /// Body size in bytes; absent for imports
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<u32>,
+}
+
+impl DeadFunctionRecord {
+    pub fn new(data: &CallGraphData, dead: &DeadFunction) -> Self {
+        DeadFunctionRecord { function: FunctionRecord::new(data, dead.func_index), size: dead.size }
+    }
+}
+
+/// The outcome of a policy rule from `--policy`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RuleRecord {
+    pub rule: String,
+    pub kind: &'static str,
+    pub passed: bool,
+    /// Example chains breaking the rule
+    pub violations: Vec<Vec<FunctionRecord>>,
+}
+
+impl RuleRecord {
+    pub fn new(data: &CallGraphData, result: &RuleResult) -> Self {
+        RuleRecord {
+            rule: result.rule.name.clone(),
+            kind: result.rule.check.kind(),
+            passed: result.passed(),
+            violations: result
+                .violations
+                .iter()
+                .map(|chain| chain.iter().map(|&idx| FunctionRecord::new(data, idx)).collect())
+                .collect(),
+        }
+    }
+}
+
+/// A capability reached by an export, from `--audit`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CapabilityRecord {
+    pub capability: Capability,
+    /// True if every chain reaching it checks authorization first
+    pub authorized: bool,
+    /// The shortest chain that doesn't
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unauthorized_chain: Option<Vec<FunctionRecord>>,
+    /// True if found by function name, for transfers not made with a constant function symbol
+    pub by_name: bool,
+}
+
+impl CapabilityRecord {
+    pub fn new(data: &CallGraphData, used: &CapabilityUse) -> Self {
+        CapabilityRecord {
+            capability: used.capability,
+            authorized: used.unauthorized.is_none(),
+            unauthorized_chain: used
+                .unauthorized
+                .as_ref()
+                .map(|chain| chain.iter().map(|&idx| FunctionRecord::new(data, idx)).collect()),
+            by_name: used.by_name,
+        }
+    }
+}
+
+/// The audit of an export from `--audit`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AuditRecord {
+    pub export: FunctionRecord,
+    /// A mutation is reachable without an authorization check first
+    pub flagged: bool,
+    pub capabilities: Vec<CapabilityRecord>,
+}
+
+impl AuditRecord {
+    pub fn new(data: &CallGraphData, audit: &ExportAudit) -> Self {
+        AuditRecord {
+            export: FunctionRecord::new(data, audit.export),
+            flagged: audit.flagged(),
+            capabilities: audit.uses.iter().map(|used| CapabilityRecord::new(data, used)).collect(),
+        }
+    }
+}
+
+/// A difference between two modules from `diff`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DiffRecord {
+    pub change: Change,
+    pub kind: DiffKind,
+    pub value: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tree: Option<TreeNodeRecord>,
+}
+
+impl DiffRecord {
+    pub fn new(entry: &DiffEntry) -> Self {
+        DiffRecord { change: entry.change, kind: entry.kind, value: entry.value.clone(), tree: entry.tree.clone() }
+    }
+}
+
+/// A host function an export can reach, from `--capabilities`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HostFunctionRecord {
+    pub module: String,
+    #[serde(flatten)]
+    pub function: FunctionRecord,
+}
+
+/// A column of the capability matrix: what one export can reach
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CapabilitiesRecord {
+    pub export: FunctionRecord,
+    pub modules: Vec<String>,
+    pub functions: Vec<HostFunctionRecord>,
+}
+
+impl CapabilitiesRecord {
+    /// The record of the export in column `column` of `matrix`
+    pub fn new(data: &CallGraphData, matrix: &CapabilityMatrix, column: usize) -> Self {
+        let reached = matrix.rows.iter().filter(|row| row.reached_by[column]);
+        let mut record = CapabilitiesRecord {
+            export: FunctionRecord::new(data, matrix.exports[column]),
+            modules: Vec::new(),
+            functions: Vec::new(),
+        };
+        for row in reached {
+            match row.function {
+                None => record.modules.push(row.module.clone()),
+                Some(idx) => record
+                    .functions
+                    .push(HostFunctionRecord { module: row.module.clone(), function: FunctionRecord::new(data, idx) }),
+            }
+        }
+        record
+    }
+}
+
+/// A chain ending in a trap, from `--traps`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TrapChainRecord {
+    #[serde(flatten)]
+    pub chain: ChainRecord,
+    pub trap: TrapKind,
+    /// Code section offsets of the `unreachable` instructions in the last function
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub unreachable_offsets: Vec<u32>,
+}
+
+impl TrapChainRecord {
+    pub fn new(data: &CallGraphData, chain: Chain, trap: TrapKind, leaves_only: bool) -> Self {
+        let unreachable_offsets = match (trap, chain.hops.last()) {
+            (TrapKind::Unreachable, Some(last)) => {
+                data.trap_sites.get(&last.func_index).into_iter().flatten().map(|site| site.offset).collect()
+            }
+            _ => Vec::new(),
+        };
+        TrapChainRecord { chain: ChainRecord::new(data, chain, leaves_only), trap, unreachable_offsets }
+    }
+}
+
+/// What env.json says about an import
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HostRecord {
+    pub module: String,
+    pub args: Vec<EnvArg>,
+    #[serde(rename = "return")]
+    pub ret: String,
+    pub docs: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_supported_protocol: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_supported_protocol: Option<u32>,
+}
+
+impl HostRecord {
+    pub fn new(host: &HostFunction) -> Self {
+        let function = &host.function;
+        HostRecord {
+            module: host.module.clone(),
+            args: function.args.clone(),
+            ret: function.ret.clone(),
+            docs: function.docs.clone(),
+            min_supported_protocol: function.min_supported_protocol,
+            max_supported_protocol: function.max_supported_protocol,
+        }
+    }
+}
+
+/// An import of the module, from `describe-import`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ImportRecord {
+    #[serde(flatten)]
+    pub function: FunctionRecord,
+    /// `module.field` it is imported as
+    pub imported_as: String,
+    #[serde(flatten)]
+    pub host: Option<HostRecord>,
+    pub callers: Vec<String>,
+}
+
+impl ImportRecord {
+    pub fn new(data: &CallGraphData, import: &ImportDescription) -> Self {
+        ImportRecord {
+            function: FunctionRecord::new(data, import.func_idx),
+            imported_as: import.import.clone(),
+            host: import.host.map(HostRecord::new),
+            callers: import.callers.clone(),
+        }
+    }
+}
+
+/// An import the checked protocol doesn't support, from `--protocol`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UnsupportedImportRecord {
+    #[serde(flatten)]
+    pub function: FunctionRecord,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_supported_protocol: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_supported_protocol: Option<u32>,
+    pub reached_by: Vec<FunctionRecord>,
+}
+
+impl UnsupportedImportRecord {
+    pub fn new(data: &CallGraphData, import: &UnsupportedImport) -> Self {
+        let host = data.host_functions.get(&import.func_idx).map(|host| &host.function);
+        UnsupportedImportRecord {
+            function: FunctionRecord::new(data, import.func_idx),
+            min_supported_protocol: host.and_then(|f| f.min_supported_protocol),
+            max_supported_protocol: host.and_then(|f| f.max_supported_protocol),
+            reached_by: import.reached_by.iter().map(|&idx| FunctionRecord::new(data, idx)).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
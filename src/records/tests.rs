@@ -0,0 +1,127 @@
+// Copyright 2025 Stellar Development Foundation and contributors. Licensed
+// under the Apache License, Version 2.0. See the COPYING file at the root
+// of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
+
+use std::ops::ControlFlow;
+
+use serde_json::json;
+
+use super::*;
+use crate::chains::{walk_call_chains, ChainOptions};
+use crate::limits::Limits;
+use crate::parsing::parse_wasm_module;
+use crate::paths::{generate_call_trees, PathOptions};
+
+fn parse_wat(wat_source: &str) -> Vec<u8> {
+    wat::parse_str(wat_source).expect("Failed to parse WAT")
+}
+
+const WAT: &str = r#"
+    (module
+        (import "env" "log" (func $log))
+        (func $main (export "main") (call $helper))
+        (func $helper (call $log))
+    )
+"#;
+
+fn chain_records(data: &CallGraphData, opts: &ChainOptions) -> Vec<serde_json::Value> {
+    let mut records = Vec::new();
+    walk_call_chains(data, opts, |chain| {
+        let record = ChainRecord::new(data, chain, opts.leaves_only);
+        records.push(serde_json::to_value(record).unwrap());
+        ControlFlow::Continue(())
+    });
+    records
+}
+
+#[test]
+fn test_chain_record() {
+    let mut data = parse_wasm_module(&parse_wat(WAT), None).unwrap();
+    // Names that would break the text format come through intact
+    data.function_names.insert(2, "helper<a,b>{}".to_string());
+
+    let dst = vec!["log".to_string()];
+    let opts = ChainOptions { dst_filter: &dst, ..Default::default() };
+    let records = chain_records(&data, &opts);
+    let site = |caller: u32| data.call_graph[&caller][0].site.unwrap();
+
+    assert_eq!(records.len(), 2);
+    assert_eq!(
+        records[0],
+        json!({
+            "chain": [
+                {"index": 1, "name": "main", "import": false, "export": true},
+                {
+                    "index": 2, "name": "helper<a,b>{}", "import": false, "export": false,
+                    "call": {"kind": "call", "offset": site(1).offset, "op_index": 0}
+                },
+                {
+                    "index": 0, "name": "log", "import": true, "export": false,
+                    "call": {"kind": "call", "offset": site(2).offset, "op_index": 0}
+                }
+            ],
+            "truncated": false
+        })
+    );
+
+    // Leaves only keeps the first and last function
+    let opts = ChainOptions { dst_filter: &dst, leaves_only: true, ..Default::default() };
+    let records = chain_records(&data, &opts);
+    assert_eq!(records.len(), 1);
+    let names: Vec<&str> = records[0]["chain"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|hop| hop["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["main", "log"]);
+}
+
+#[test]
+fn test_tree_record() {
+    let data = parse_wasm_module(&parse_wat(WAT), None).unwrap();
+    let src = vec!["main".to_string()];
+    let opts = PathOptions {
+        src_filter: &src,
+        limits: Limits { max_depth: Some(2), max_chains: None },
+        ..Default::default()
+    };
+    let (trees, _) = generate_call_trees(&data, &opts);
+
+    // Nodes nest like the CallNode tree, and cut-off calls become a marker
+    let record = serde_json::to_value(TreeRecord::new(&data, &trees[0])).unwrap();
+    assert_eq!(record["tree"]["name"], "main");
+    assert_eq!(record["tree"]["export"], true);
+    assert!(record["tree"].get("call").is_none());
+    let helper = &record["tree"]["children"][0];
+    assert_eq!(helper["name"], "helper");
+    assert_eq!(helper["call"]["kind"], "call");
+    assert_eq!(helper["children"], json!([{"truncated": true}]));
+}
+
+#[test]
+fn test_implicit_call_and_count_records() {
+    let data = parse_wasm_module(&parse_wat(WAT), None).unwrap();
+
+    // Implicit edges have no call site
+    let edge = CallEdge::implicit(1);
+    assert_eq!(serde_json::to_value(CallRecord::new(&data, &edge)).unwrap(), json!({"kind": "implicit"}));
+
+    let count = ChainCount {
+        src_index: 1,
+        src: "main".to_string(),
+        dst_index: 0,
+        dst: "log".to_string(),
+        count: 3,
+        exact: false,
+    };
+    let record = serde_json::to_value(CountRecord::new(&data, &count)).unwrap();
+    assert_eq!(record["dst"]["import"], true);
+    assert_eq!(record["count"], 3);
+    assert_eq!(record["exact"], false);
+
+    // Saturated counts still print as plain numbers
+    let count = ChainCount { count: u128::MAX, ..count };
+    let text = serde_json::to_string(&CountRecord::new(&data, &count)).unwrap();
+    assert!(text.contains(&format!(r#""count":{},"#, u128::MAX)));
+}
@@ -0,0 +1,182 @@
+// Copyright 2025 Stellar Development Foundation and contributors. Licensed
+// under the Apache License, Version 2.0. See the COPYING file at the root
+// of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
+
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::parsing::CallGraphData;
+
+/// The call graph condensed into its strongly connected components
+#[derive(Debug)]
+pub struct Condensation {
+    /// Members of each component, in reverse topological order: every
+    /// component only calls into components that come before it
+    pub components: Vec<Vec<u32>>,
+    /// Component index of every function
+    pub component_of: HashMap<u32, usize>,
+}
+
+impl Condensation {
+    /// A component is recursive if it has more than one member, or its one
+    /// member calls itself
+    pub fn is_recursive(&self, data: &CallGraphData, component: usize) -> bool {
+        match self.components[component].as_slice() {
+            [single] => data.call_graph.get(single).is_some_and(|edges| edges.iter().any(|e| e.callee == *single)),
+            _ => true,
+        }
+    }
+}
+
+/// A set of mutually recursive functions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecursionCycle {
+    /// Sorted by function index
+    pub members: Vec<u32>,
+    /// A shortest cycle through the first member, starting and ending with it
+    pub example: Vec<u32>,
+}
+
+impl RecursionCycle {
+    /// Format as `a,b,c: a,b,a`: the members, then the example cycle
+    pub fn to_line(&self, data: &CallGraphData) -> String {
+        let names = |funcs: &[u32]| {
+            funcs
+                .iter()
+                .map(|&idx| data.function_name(idx))
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        format!("{}: {}", names(&self.members), names(&self.example))
+    }
+}
+
+/// Find every recursive component of the call graph: groups of mutually recursive
+/// functions, and functions that call themselves. Largest cycles come first,
+/// then by lowest function index.
+pub fn find_cycles(data: &CallGraphData) -> Vec<RecursionCycle> {
+    let condensation = condense(data);
+    let mut cycles: Vec<RecursionCycle> = (0..condensation.components.len())
+        .filter(|&c| condensation.is_recursive(data, c))
+        .map(|c| {
+            let members = condensation.components[c].clone();
+            let example = shortest_cycle(data, &condensation, c, members[0]);
+            RecursionCycle { members, example }
+        })
+        .collect();
+    cycles.sort_by_key(|cycle| (Reverse(cycle.members.len()), cycle.members[0]));
+    cycles
+}
+
+/// Breadth-first search inside a component for the shortest way from `start`
+/// back to itself
+fn shortest_cycle(data: &CallGraphData, condensation: &Condensation, component: usize, start: u32) -> Vec<u32> {
+    let mut came_from: HashMap<u32, u32> = HashMap::new();
+    let mut queue: VecDeque<u32> = VecDeque::from([start]);
+
+    while let Some(func_idx) = queue.pop_front() {
+        for edge in data.call_graph.get(&func_idx).into_iter().flatten() {
+            if condensation.component_of[&edge.callee] != component {
+                continue;
+            }
+            if edge.callee == start {
+                let mut cycle = vec![start, func_idx];
+                while let Some(&prev) = came_from.get(cycle.last().unwrap()) {
+                    cycle.push(prev);
+                }
+                cycle.reverse();
+                return cycle;
+            }
+            if let Entry::Vacant(entry) = came_from.entry(edge.callee) {
+                entry.insert(func_idx);
+                queue.push_back(edge.callee);
+            }
+        }
+    }
+
+    // Unreachable for recursive components, which always contain a cycle
+    vec![start]
+}
+
+/// Every function that is part of a recursion cycle
+pub fn recursive_functions(data: &CallGraphData) -> HashSet<u32> {
+    let condensation = condense(data);
+    (0..condensation.components.len())
+        .filter(|&c| condensation.is_recursive(data, c))
+        .flat_map(|c| condensation.components[c].iter().copied())
+        .collect()
+}
+
+/// Compute the strongly connected components of the call graph with Tarjan's
+/// algorithm. Imports and defined functions are all included. The traversal
+/// is iterative so deep call graphs don't overflow the stack.
+pub fn condense(data: &CallGraphData) -> Condensation {
+    let mut nodes: Vec<u32> = data.imported_functions.iter().copied().collect();
+    nodes.sort();
+    nodes.extend(&data.all_function_indices);
+
+    let mut index_of: HashMap<u32, usize> = HashMap::new();
+    let mut lowlink: HashMap<u32, usize> = HashMap::new();
+    let mut on_stack: HashMap<u32, bool> = HashMap::new();
+    let mut stack: Vec<u32> = Vec::new();
+    let mut components: Vec<Vec<u32>> = Vec::new();
+    let mut component_of: HashMap<u32, usize> = HashMap::new();
+
+    for &root in &nodes {
+        if index_of.contains_key(&root) {
+            continue;
+        }
+
+        // Each frame is a function and how many of its edges we've looked at
+        let mut frames: Vec<(u32, usize)> = vec![(root, 0)];
+        while let Some(&mut (node, ref mut next_edge)) = frames.last_mut() {
+            if *next_edge == 0 && !index_of.contains_key(&node) {
+                let index = index_of.len();
+                index_of.insert(node, index);
+                lowlink.insert(node, index);
+                stack.push(node);
+                on_stack.insert(node, true);
+            }
+
+            let edges = data.call_graph.get(&node).map_or(&[][..], |e| e.as_slice());
+            if let Some(edge) = edges.get(*next_edge) {
+                *next_edge += 1;
+                let callee = edge.callee;
+                if !index_of.contains_key(&callee) {
+                    frames.push((callee, 0));
+                } else if on_stack.get(&callee).copied().unwrap_or(false) {
+                    let low = lowlink[&node].min(index_of[&callee]);
+                    lowlink.insert(node, low);
+                }
+                continue;
+            }
+
+            // All edges done: pop the frame and report back to the caller
+            frames.pop();
+            if let Some(&(caller, _)) = frames.last() {
+                let low = lowlink[&caller].min(lowlink[&node]);
+                lowlink.insert(caller, low);
+            }
+
+            if lowlink[&node] == index_of[&node] {
+                let mut members = Vec::new();
+                while let Some(member) = stack.pop() {
+                    on_stack.insert(member, false);
+                    component_of.insert(member, components.len());
+                    members.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                members.sort();
+                components.push(members);
+            }
+        }
+    }
+
+    Condensation { components, component_of }
+}
+
+#[cfg(test)]
+mod tests;
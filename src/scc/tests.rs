@@ -0,0 +1,92 @@
+// Copyright 2025 Stellar Development Foundation and contributors. Licensed
+// under the Apache License, Version 2.0. See the COPYING file at the root
+// of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use crate::parsing::parse_wasm_module;
+
+fn parse_wat(wat_source: &str) -> Vec<u8> {
+    wat::parse_str(wat_source).expect("Failed to parse WAT")
+}
+
+#[test]
+fn test_components_and_order() {
+    let wasm = parse_wat(
+        r#"
+        (module
+            (import "env" "log" (func $log))
+            (func $main (export "main") (call $ping) (call $self))
+            (func $ping (call $pong))
+            (func $pong (call $ping) (call $log))
+            (func $self (call $self))
+        )
+        "#,
+    );
+
+    let data = parse_wasm_module(&wasm, None).unwrap();
+    let condensation = condense(&data);
+
+    // Every function belongs to exactly one component
+    assert_eq!(condensation.component_of.len(), 5);
+    assert_eq!(condensation.components.len(), 4);
+
+    let ping = condensation.component_of[&2];
+    assert_eq!(condensation.components[ping], vec![2, 3]);
+    assert_eq!(condensation.component_of[&3], ping);
+
+    // Callees come before their callers
+    let main = condensation.component_of[&1];
+    let log = condensation.component_of[&0];
+    let self_rec = condensation.component_of[&4];
+    assert!(log < ping && ping < main && self_rec < main);
+
+    assert!(condensation.is_recursive(&data, ping));
+    assert!(condensation.is_recursive(&data, self_rec));
+    assert!(!condensation.is_recursive(&data, main));
+    assert!(!condensation.is_recursive(&data, log));
+}
+
+#[test]
+fn test_find_cycles() {
+    let wasm = parse_wat(
+        r#"
+        (module
+            (func $main (export "main") (call $a) (call $self))
+            (func $a (call $b))
+            (func $b (call $c) (call $leaf))
+            (func $c (call $a) (call $b))
+            (func $self (call $leaf) (call $self))
+            (func $leaf)
+        )
+        "#,
+    );
+
+    let data = parse_wasm_module(&wasm, None).unwrap();
+    let cycles = find_cycles(&data);
+
+    // The larger cycle comes first; its example is the shortest way back to `a`
+    let lines: Vec<String> = cycles.iter().map(|c| c.to_line(&data)).collect();
+    assert_eq!(lines, vec!["a,b,c: a,b,c,a", "self: self,self"]);
+
+    let recursive = recursive_functions(&data);
+    let mut recursive: Vec<u32> = recursive.into_iter().collect();
+    recursive.sort();
+    assert_eq!(recursive, vec![1, 2, 3, 4]);
+}
+
+#[test]
+fn test_deep_chain_does_not_overflow() {
+    let count = 20_000;
+    let mut wat = String::from("(module (func $f0 (export \"main\") (call $f1))\n");
+    for i in 1..count {
+        wat.push_str(&format!("(func $f{} (call $f{}))\n", i, (i + 1) % count));
+    }
+    wat.push(')');
+
+    let data = parse_wasm_module(&parse_wat(&wat), None).unwrap();
+    let condensation = condense(&data);
+
+    // One big cycle through every function
+    assert_eq!(condensation.components.len(), 1);
+    assert_eq!(condensation.components[0].len(), count);
+}
@@ -0,0 +1,148 @@
+// Copyright 2025 Stellar Development Foundation and contributors. Licensed
+// under the Apache License, Version 2.0. See the COPYING file at the root
+// of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::chains::{ChainOptions, Hop};
+use crate::parsing::{CallGraphData, RootKind};
+
+/// A chain as a start function and the calls taken from it, each call being a
+/// caller and the position of the edge in its `call_graph` entry
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct Route {
+    start: u32,
+    calls: Vec<(u32, usize)>,
+}
+
+impl Route {
+    fn nodes<'a>(&'a self, data: &'a CallGraphData) -> impl Iterator<Item = u32> + 'a {
+        std::iter::once(self.start).chain(self.calls.iter().map(|&(caller, pos)| data.call_graph[&caller][pos].callee))
+    }
+
+    fn to_hops(&self, data: &CallGraphData) -> Vec<Hop> {
+        let mut hops = vec![Hop { func_index: self.start, edge: None }];
+        hops.extend(self.calls.iter().map(|&(caller, pos)| {
+            let edge = data.call_graph[&caller][pos];
+            Hop { func_index: edge.callee, edge: Some(edge) }
+        }));
+        hops
+    }
+}
+
+/// Breadth-first search for a shortest route from `from` to `to` that avoids
+/// the given functions and calls. Ties go to the calls made first.
+fn shortest_route(
+    data: &CallGraphData,
+    from: u32,
+    to: u32,
+    removed_funcs: &HashSet<u32>,
+    removed_calls: &HashSet<(u32, usize)>,
+) -> Option<Route> {
+    let mut came_from: HashMap<u32, (u32, usize)> = HashMap::new();
+    let mut seen: HashSet<u32> = HashSet::from([from]);
+    let mut queue: VecDeque<u32> = VecDeque::from([from]);
+
+    while let Some(func_idx) = queue.pop_front() {
+        if func_idx == to {
+            let mut calls = Vec::new();
+            let mut current = to;
+            while let Some(&(caller, pos)) = came_from.get(&current) {
+                calls.push((caller, pos));
+                current = caller;
+            }
+            calls.reverse();
+            return Some(Route { start: from, calls });
+        }
+        for (pos, edge) in data.call_graph.get(&func_idx).into_iter().flatten().enumerate() {
+            if removed_funcs.contains(&edge.callee) || removed_calls.contains(&(func_idx, pos)) {
+                continue;
+            }
+            if seen.insert(edge.callee) {
+                came_from.insert(edge.callee, (func_idx, pos));
+                queue.push_back(edge.callee);
+            }
+        }
+    }
+    None
+}
+
+/// Yen's algorithm: the `k` shortest routes from `from` to `to` that never visit
+/// a function twice, shortest first
+fn k_shortest_routes(data: &CallGraphData, from: u32, to: u32, k: usize) -> Vec<Route> {
+    let Some(first) = shortest_route(data, from, to, &HashSet::new(), &HashSet::new()) else {
+        return Vec::new();
+    };
+    let mut found = vec![first];
+    let mut candidates: Vec<Route> = Vec::new();
+
+    while found.len() < k {
+        let previous = found.last().unwrap().clone();
+        let previous_nodes: Vec<u32> = previous.nodes(data).collect();
+
+        // Branch off the previous route at each of its functions in turn
+        for i in 0..previous.calls.len() {
+            let spur = previous_nodes[i];
+            let root = &previous.calls[..i];
+
+            // Don't retake a call that a known route with the same beginning took
+            let removed_calls: HashSet<(u32, usize)> = found
+                .iter()
+                .filter(|route| route.calls.len() > i && route.calls[..i] == *root)
+                .map(|route| route.calls[i])
+                .collect();
+            let removed_funcs: HashSet<u32> = previous_nodes[..i].iter().copied().collect();
+
+            if let Some(spur_route) = shortest_route(data, spur, to, &removed_funcs, &removed_calls) {
+                let mut calls = root.to_vec();
+                calls.extend(spur_route.calls);
+                let route = Route { start: from, calls };
+                if !found.contains(&route) && !candidates.contains(&route) {
+                    candidates.push(route);
+                }
+            }
+        }
+
+        let Some(best) = candidates
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, route)| (route.calls.len(), *route))
+            .map(|(i, _)| i)
+        else {
+            break;
+        };
+        found.push(candidates.swap_remove(best));
+    }
+
+    found
+}
+
+/// Find the `k` shortest chains from each starting function to each destination,
+/// for when a few witnesses are enough. Sources are chosen as in chain
+/// enumeration; destinations are the functions named by `dst_filter`, or all
+/// imports. Like enumerated chains, a chain never visits a function twice, and
+/// calls from different call sites make different chains.
+///
+/// Chains are grouped by starting function index, then destination name, and
+/// are shortest first within each group. `limits` are ignored.
+pub fn shortest_call_chains(data: &CallGraphData, opts: &ChainOptions, k: usize) -> Vec<Vec<Hop>> {
+    let roots = match opts.roots {
+        None if opts.leaves_only => Some(&[RootKind::Export][..]),
+        roots => roots,
+    };
+    let start_functions = data.start_functions(opts.src_filter, roots);
+    let destinations = data.end_functions(opts.dst_filter);
+
+    let mut chains = Vec::new();
+    for &src in &start_functions {
+        for &dst in &destinations {
+            for route in k_shortest_routes(data, src, dst, k) {
+                chains.push(route.to_hops(data));
+            }
+        }
+    }
+    chains
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,90 @@
+// Copyright 2025 Stellar Development Foundation and contributors. Licensed
+// under the Apache License, Version 2.0. See the COPYING file at the root
+// of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
+
+use super::*;
+use crate::chains::{enumerate_call_chains, render_chain, Chain};
+use crate::parsing::parse_wasm_module;
+
+fn parse_wat(wat_source: &str) -> Vec<u8> {
+    wat::parse_str(wat_source).expect("Failed to parse WAT")
+}
+
+fn shortest(data: &CallGraphData, opts: &ChainOptions, k: usize) -> Vec<String> {
+    shortest_call_chains(data, opts, k)
+        .iter()
+        .map(|hops| render_chain(data, Chain { hops, truncated: false, truncated_start: false }, opts))
+        .collect()
+}
+
+const LAYERED: &str = r#"
+    (module
+        (import "env" "log" (func $log))
+        (import "env" "put" (func $put))
+        (func $main (export "main") (call $a) (call $b) (call $log))
+        (func $a (call $b) (call $put))
+        (func $b (call $c) (call $log))
+        (func $c (call $a) (call $log) (call $put))
+    )
+"#;
+
+#[test]
+fn test_shortest_chain_per_pair() {
+    let data = parse_wasm_module(&parse_wat(LAYERED), None).unwrap();
+    let src = vec!["main".to_string()];
+    let opts = ChainOptions { src_filter: &src, ..Default::default() };
+
+    // One chain for each import, ties broken by call order
+    assert_eq!(shortest(&data, &opts, 1), vec!["main,log", "main,a,put"]);
+}
+
+#[test]
+fn test_k_shortest_match_enumeration() {
+    let data = parse_wasm_module(&parse_wat(LAYERED), None).unwrap();
+    let src = vec!["main".to_string()];
+    let dst = vec!["put".to_string()];
+    let opts = ChainOptions { src_filter: &src, dst_filter: &dst, ..Default::default() };
+
+    let (mut all, _) = enumerate_call_chains(&data, &opts);
+    all.sort_by_key(|chain| chain.matches(',').count());
+
+    // Asking for more than there are returns every chain, shortest first
+    let found = shortest(&data, &opts, 100);
+    assert_eq!(found.len(), all.len());
+    for (found, expected) in found.iter().zip(&all) {
+        assert_eq!(found.matches(',').count(), expected.matches(',').count());
+        assert!(all.contains(found));
+    }
+
+    assert_eq!(shortest(&data, &opts, 3), vec!["main,a,put", "main,b,c,put", "main,a,b,c,put"]);
+}
+
+#[test]
+fn test_call_sites_make_different_chains() {
+    let wasm = parse_wat(
+        r#"
+        (module
+            (import "env" "log" (func $log))
+            (func $main (export "main") (call $log) (call $log))
+        )
+        "#,
+    );
+
+    let data = parse_wasm_module(&wasm, None).unwrap();
+    let chains = shortest_call_chains(&data, &ChainOptions::default(), 5);
+    assert_eq!(chains.len(), 2);
+    assert_ne!(chains[0][1].edge, chains[1][1].edge);
+}
+
+#[test]
+fn test_no_chain() {
+    let data = parse_wasm_module(&parse_wat(LAYERED), None).unwrap();
+    let src = vec!["c".to_string()];
+    let dst = vec!["main".to_string()];
+    let opts = ChainOptions { src_filter: &src, dst_filter: &dst, ..Default::default() };
+    assert!(shortest_call_chains(&data, &opts, 3).is_empty());
+
+    // Leaves-only output still works, and starts from exports
+    let opts = ChainOptions { leaves_only: true, ..Default::default() };
+    assert_eq!(shortest(&data, &opts, 1), vec!["main,log", "main,put"]);
+}
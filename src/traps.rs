@@ -0,0 +1,57 @@
+// Copyright 2025 Stellar Development Foundation and contributors. Licensed
+// under the Apache License, Version 2.0. See the COPYING file at the root
+// of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
+
+use std::ops::ControlFlow;
+
+use crate::chains::{render_chain, walk_call_chains, Chain, ChainOptions};
+use crate::limits::Truncation;
+use crate::parsing::{CallGraphData, RootKind, TrapKind};
+
+/// DFS over the call chains that end in a function that can trap, calling
+/// `visit` with each chain and how its last function traps. Chains start
+/// from exports, or from every defined function named in `src_filter`, and
+/// go on past functions containing `unreachable`, since the instruction may
+/// be on a branch that isn't taken. Use `stop_at_traps` first to keep the
+/// chains from running through panic helpers. `dst_filter` is ignored, and
+/// `leaves_only` doesn't drop chains that end in a defined function: it only
+/// shortens them when rendered.
+pub fn walk_trap_chains<F>(data: &CallGraphData, opts: &ChainOptions, mut visit: F) -> Truncation
+where
+    F: FnMut(Chain, TrapKind) -> ControlFlow<()>,
+{
+    let mut trap_names: Vec<String> = data
+        .imported_functions
+        .iter()
+        .chain(&data.all_function_indices)
+        .filter(|&&idx| data.trap_kind(idx).is_some())
+        .filter_map(|idx| data.function_names.get(idx).cloned())
+        .collect();
+    if trap_names.is_empty() {
+        return Truncation::default();
+    }
+    trap_names.sort();
+    trap_names.dedup();
+
+    let roots = match opts.roots {
+        None if opts.src_filter.is_empty() => Some(&[RootKind::Export][..]),
+        roots => roots,
+    };
+    let trap_opts = ChainOptions { dst_filter: &trap_names, roots, leaves_only: false, ..opts.clone() };
+    walk_call_chains(data, &trap_opts, |chain| {
+        // Another function may share a trapping function's name
+        match chain.hops.last().and_then(|hop| data.trap_kind(hop.func_index)) {
+            Some(kind) => visit(chain, kind),
+            None => ControlFlow::Continue(()),
+        }
+    })
+}
+
+/// Format a trap chain as `a,b,c (KIND)`, shortened as `opts` says: with
+/// `leaves_only`, to `a,c (KIND)`
+pub fn render_trap_chain(data: &CallGraphData, chain: Chain, kind: TrapKind, opts: &ChainOptions) -> String {
+    format!("{} ({})", render_chain(data, chain, opts), kind)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,114 @@
+// Copyright 2025 Stellar Development Foundation and contributors. Licensed
+// under the Apache License, Version 2.0. See the COPYING file at the root
+// of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
+
+use std::collections::HashMap;
+
+use super::*;
+use crate::parsing::{parse_wasm_module, stop_at_traps};
+
+fn parse_wat(wat_source: &str) -> Vec<u8> {
+    wat::parse_str(wat_source).expect("Failed to parse WAT")
+}
+
+fn trap_chains(data: &CallGraphData, opts: &ChainOptions) -> Vec<String> {
+    let mut chains = Vec::new();
+    walk_trap_chains(data, opts, |chain, kind| {
+        chains.push(render_trap_chain(data, chain, kind, opts));
+        ControlFlow::Continue(())
+    });
+    chains
+}
+
+const CONTRACT: &str = r#"
+    (module
+        (import "x" "5" (func $fail_with_error (param i64)))
+        (import "x" "0" (func $log))
+        (func $transfer (export "transfer") (call $check) (call $spend))
+        (func $balance (export "balance") (call $log))
+        (func $check (call $fail_with_error (i64.const 0)))
+        (func $spend (call $log) (call $core::panicking::panic_fmt))
+        (func $core::panicking::panic_fmt (call $fmt) unreachable)
+        (func $fmt (call $log) unreachable)
+        (func $guard (export "guard") (if (i32.const 0) (then unreachable)) (call $log))
+    )
+"#;
+
+fn contract() -> CallGraphData {
+    let env: HashMap<String, String> = [("x.5", "fail_with_error"), ("x.0", "log")]
+        .into_iter()
+        .map(|(key, name)| (key.to_string(), name.to_string()))
+        .collect();
+    parse_wasm_module(&parse_wat(CONTRACT), Some(&env)).unwrap()
+}
+
+#[test]
+fn test_trap_kinds() {
+    let data = contract();
+    let kind = |name: &str| {
+        let idx = data.function_names.iter().find(|(_, n)| *n == name).map(|(idx, _)| *idx).unwrap();
+        data.trap_kind(idx)
+    };
+    assert_eq!(kind("fail_with_error"), Some(TrapKind::FailWithError));
+    assert_eq!(kind("log"), None);
+    assert_eq!(kind("core::panicking::panic_fmt"), Some(TrapKind::Panic));
+    assert_eq!(kind("fmt"), Some(TrapKind::Unreachable));
+    assert_eq!(kind("guard"), Some(TrapKind::Unreachable));
+    assert_eq!(kind("transfer"), None);
+    assert_eq!(data.trap_sites[&8].len(), 1);
+}
+
+#[test]
+fn test_panic_helper_paths() {
+    let data = parse_wasm_module(
+        &parse_wat(
+            r#"
+            (module
+                (func $core::result::unwrap_failed::h0123456789abcdef)
+                (func $std::panicking::begin_panic_handler)
+                (func $contract::panic_if_paused)
+                (func $unwrap_failed_count)
+            )
+            "#,
+        ),
+        None,
+    )
+    .unwrap();
+    let kinds: Vec<Option<TrapKind>> = (0..4).map(|idx| data.trap_kind(idx)).collect();
+    assert_eq!(kinds, vec![Some(TrapKind::Panic), Some(TrapKind::Panic), None, None]);
+}
+
+#[test]
+fn test_trap_chains() {
+    let mut data = contract();
+    stop_at_traps(&mut data);
+    let opts = ChainOptions::default();
+
+    // Panic helpers are leaves, and chains go on past an unreachable instruction
+    assert_eq!(
+        trap_chains(&data, &opts),
+        vec![
+            "transfer,check,fail_with_error (fail_with_error)",
+            "transfer,spend,core::panicking::panic_fmt (panic)",
+            "guard (unreachable)",
+        ]
+    );
+
+    let src = vec!["spend".to_string()];
+    let opts = ChainOptions { src_filter: &src, leaves_only: true, ..Default::default() };
+    assert_eq!(trap_chains(&data, &opts), vec!["spend,core::panicking::panic_fmt (panic)"]);
+}
+
+#[test]
+fn test_without_stop_at_traps() {
+    let data = contract();
+    let src = vec!["spend".to_string()];
+    let opts = ChainOptions { src_filter: &src, ..Default::default() };
+    assert_eq!(
+        trap_chains(&data, &opts),
+        vec![
+            "spend,core::panicking::panic_fmt (panic)",
+            "spend,core::panicking::panic_fmt,fmt (unreachable)",
+        ]
+    );
+}
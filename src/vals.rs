@@ -0,0 +1,219 @@
+// Copyright 2025 Stellar Development Foundation and contributors. Licensed
+// under the Apache License, Version 2.0. See the COPYING file at the root
+// of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
+
+use crate::parsing::{CallEdge, CallGraphData};
+
+/// Names of the object tags of a Soroban `Val`, starting at tag 64
+const OBJECT_TAGS: [&str; 15] = [
+    "U64Object",
+    "I64Object",
+    "TimepointObject",
+    "DurationObject",
+    "U128Object",
+    "I128Object",
+    "U256Object",
+    "I256Object",
+    "BytesObject",
+    "StringObject",
+    "SymbolObject",
+    "VecObject",
+    "MapObject",
+    "AddressObject",
+    "MuxedAddressObject",
+];
+
+/// `ScErrorType` names, by the minor part of an `Error` value
+const ERROR_TYPES: [&str; 10] =
+    ["Contract", "WasmVm", "Context", "Storage", "Object", "Crypto", "Events", "Budget", "Value", "Auth"];
+
+/// `ScErrorCode` names, by the major part of a non-contract `Error` value
+const ERROR_CODES: [&str; 10] = [
+    "ArithDomain",
+    "IndexBounds",
+    "InvalidInput",
+    "MissingValue",
+    "ExistingValue",
+    "ExceededLimit",
+    "InvalidAction",
+    "InternalError",
+    "UnexpectedType",
+    "UnexpectedSize",
+];
+
+/// Host functions that read a string from linear memory, with the indices of
+/// the arguments holding its position and its length
+const MEMORY_STRING_FUNCTIONS: [(&str, usize, usize); 4] = [
+    ("log_from_linear_memory", 0, 1),
+    ("symbol_new_from_linear_memory", 0, 1),
+    ("string_new_from_linear_memory", 0, 1),
+    ("bytes_new_from_linear_memory", 0, 1),
+];
+
+/// Host functions that read an array of `(pos, len)` string slices from linear
+/// memory, e.g. the field names of a contract type, with the indices of the
+/// arguments holding the array's position and its number of slices
+const MEMORY_SLICES_FUNCTIONS: [(&str, usize, usize); 2] =
+    [("map_new_from_linear_memory", 0, 2), ("symbol_index_in_linear_memory", 1, 2)];
+
+/// Decode the characters of a `SymbolSmall` body: up to 9 characters of 6
+/// bits each, the first one in the highest bits
+fn decode_small_symbol(mut body: u64) -> Option<String> {
+    let mut chars = Vec::new();
+    while body != 0 {
+        let code = (body & 0x3f) as u8;
+        chars.push(match code {
+            1 => '_',
+            2..=11 => (b'0' + code - 2) as char,
+            12..=37 => (b'A' + code - 12) as char,
+            38..=63 => (b'a' + code - 38) as char,
+            _ => return None,
+        });
+        body >>= 6;
+    }
+    chars.reverse();
+    Some(chars.into_iter().collect())
+}
+
+/// Decode a Soroban `Val` from its 64-bit representation, e.g.
+/// `Symbol("admin")`, `U32(7)` or `Error(Contract, #3)`. The low 8 bits are
+/// the tag; small values and object handles live in the rest. Returns None
+/// for tags that don't exist.
+pub fn decode_val(raw: i64) -> Option<String> {
+    let bits = raw as u64;
+    let tag = (bits & 0xff) as u8;
+    let body = bits >> 8;
+    let signed_body = raw >> 8;
+    let major = (bits >> 32) as u32;
+    let minor = ((bits >> 8) & 0xff_ffff) as u32;
+    let decoded = match tag {
+        0 => "false".to_string(),
+        1 => "true".to_string(),
+        2 => "void".to_string(),
+        3 => {
+            let error_type = ERROR_TYPES.get(minor as usize)?;
+            match ERROR_CODES.get(major as usize) {
+                Some(code) if minor != 0 => format!("Error({}, {})", error_type, code),
+                _ => format!("Error({}, #{})", error_type, major),
+            }
+        }
+        4 => format!("U32({})", major),
+        5 => format!("I32({})", major as i32),
+        6 => format!("U64({})", body),
+        7 => format!("I64({})", signed_body),
+        8 => format!("Timepoint({})", body),
+        9 => format!("Duration({})", body),
+        10 => format!("U128({})", body),
+        11 => format!("I128({})", signed_body),
+        12 => format!("U256({})", body),
+        13 => format!("I256({})", signed_body),
+        14 => format!("Symbol({:?})", decode_small_symbol(body)?),
+        64..=78 => format!("{}(#{})", OBJECT_TAGS[tag as usize - 64], major),
+        _ => return None,
+    };
+    Some(decoded)
+}
+
+/// Decode a constant argument of a host function given its env.json type.
+/// Anything that isn't a valid value of the type is shown in hex.
+pub fn decode_arg(raw: i64, ty: &str) -> String {
+    let decoded = match ty {
+        "StorageType" => match raw {
+            0 => Some("Temporary".to_string()),
+            1 => Some("Persistent".to_string()),
+            2 => Some("Instance".to_string()),
+            _ => None,
+        },
+        "u64" => Some((raw as u64).to_string()),
+        "i64" => Some(raw.to_string()),
+        _ => decode_val(raw),
+    };
+    decoded.unwrap_or_else(|| format!("{:#x}", raw))
+}
+
+/// The number in a constant `U32Val` argument
+fn u32_val(value: Option<i64>) -> Option<u32> {
+    let raw = value?;
+    (raw & 0xff == 4).then_some((raw >> 32) as u32)
+}
+
+/// The name in a constant `SymbolSmall` argument
+pub fn symbol_val(value: Option<i64>) -> Option<String> {
+    let raw = value?;
+    (raw & 0xff == 14).then(|| decode_small_symbol(raw as u64 >> 8))?
+}
+
+/// Format bytes read from linear memory as a quoted string, or in hex if they
+/// aren't UTF-8
+fn format_memory_string(bytes: &[u8]) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => format!("{:?}", text),
+        Err(_) => format!("0x{}", bytes.iter().map(|b| format!("{:02x}", b)).collect::<String>()),
+    }
+}
+
+/// Resolve the string a call to a linear memory host function reads, when its
+/// position and length are constants pointing into a data segment. Returns the
+/// indices of the position and length arguments, and the string.
+fn memory_string(data: &CallGraphData, callee: u32, values: &[Option<i64>]) -> Option<(usize, usize, String)> {
+    let name = data.function_names.get(&callee)?;
+    let arg = |i: usize| u32_val(*values.get(i)?);
+    if let Some(&(_, pos, len)) = MEMORY_STRING_FUNCTIONS.iter().find(|(f, _, _)| f == name) {
+        let bytes = data.read_memory(arg(pos)?, arg(len)?)?;
+        return Some((pos, len, format_memory_string(bytes)));
+    }
+    let &(_, pos, len) = MEMORY_SLICES_FUNCTIONS.iter().find(|(f, _, _)| f == name)?;
+    let count = arg(len)?;
+    let slices = data.read_memory(arg(pos)?, count.checked_mul(8)?)?;
+    let strings = slices
+        .chunks(8)
+        .map(|slice| {
+            let word = |at: usize| u32::from_le_bytes(slice[at..at + 4].try_into().unwrap());
+            Some(format_memory_string(data.read_memory(word(0), word(4))?))
+        })
+        .collect::<Option<Vec<String>>>()?;
+    Some((pos, len, format!("[{}]", strings.join(", "))))
+}
+
+/// The constant arguments of a call to an import with env.json argument
+/// types, decoded, as (name, value) pairs in argument order. A position and
+/// length pointing to a string in a data segment are shown as that string,
+/// named after the position without its `_pos` suffix.
+pub fn call_args(data: &CallGraphData, edge: &CallEdge) -> Vec<(String, String)> {
+    let (Some(site), Some(host)) = (edge.site, data.host_functions.get(&edge.callee)) else {
+        return Vec::new();
+    };
+    let Some(values) = data.call_args.get(&site.offset) else {
+        return Vec::new();
+    };
+    let params = &host.function.args;
+    let mut args: Vec<Option<(String, String)>> = params
+        .iter()
+        .zip(values)
+        .map(|(param, value)| Some((param.name.clone(), decode_arg((*value)?, &param.ty))))
+        .collect();
+    let string = memory_string(data, edge.callee, values).filter(|(pos, len, _)| *pos.max(len) < args.len());
+    if let Some((pos, len, string)) = string {
+        let name = params[pos].name.trim_end_matches("_pos").to_string();
+        args[pos] = Some((name, string));
+        args[len] = None;
+    }
+    args.into_iter().flatten().collect()
+}
+
+/// Format the decoded constant arguments of a call as `(k=Symbol("admin"), t=Persistent)`,
+/// or an empty string if there are none
+pub fn format_call_args(data: &CallGraphData, edge: &CallEdge) -> String {
+    let args: Vec<String> = call_args(data, edge)
+        .into_iter()
+        .map(|(name, value)| format!("{}={}", name, value))
+        .collect();
+    if args.is_empty() {
+        String::new()
+    } else {
+        format!("({})", args.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,176 @@
+// Copyright 2025 Stellar Development Foundation and contributors. Licensed
+// under the Apache License, Version 2.0. See the COPYING file at the root
+// of this distribution or at http://www.apache.org/licenses/LICENSE-2.0
+
+use std::collections::HashMap;
+
+use super::*;
+use crate::parsing::{apply_env_functions, parse_wasm_module, EnvArg, EnvFunction, HostFunction};
+
+fn parse_wat(wat_source: &str) -> Vec<u8> {
+    wat::parse_str(wat_source).expect("Failed to parse WAT")
+}
+
+/// Encode a small symbol the way the SDK does
+fn symbol(s: &str) -> i64 {
+    let body = s.bytes().fold(0i64, |acc, c| {
+        let code = match c {
+            b'_' => 1,
+            b'0'..=b'9' => c - b'0' + 2,
+            b'A'..=b'Z' => c - b'A' + 12,
+            _ => c - b'a' + 38,
+        };
+        (acc << 6) | code as i64
+    });
+    (body << 8) | 14
+}
+
+/// A host function imported as `module_export.export`, with the given argument names and types
+fn host(module_export: &str, export: &str, name: &str, args: &[(&str, &str)]) -> (String, HostFunction) {
+    let function = EnvFunction {
+        export: export.to_string(),
+        name: name.to_string(),
+        args: args
+            .iter()
+            .map(|(name, ty)| EnvArg { name: name.to_string(), ty: ty.to_string() })
+            .collect(),
+        ret: "Val".to_string(),
+        docs: String::new(),
+        min_supported_protocol: None,
+        max_supported_protocol: None,
+    };
+    let host = HostFunction { module: String::new(), module_export: module_export.to_string(), function };
+    (format!("{}.{}", module_export, export), host)
+}
+
+#[test]
+fn test_decode_val() {
+    assert_eq!(decode_val(symbol("admin")).unwrap(), "Symbol(\"admin\")");
+    assert_eq!(decode_val(symbol("Balance_9")).unwrap(), "Symbol(\"Balance_9\")");
+    assert_eq!(decode_val(1).unwrap(), "true");
+    assert_eq!(decode_val(2).unwrap(), "void");
+    assert_eq!(decode_val((7 << 32) | 4).unwrap(), "U32(7)");
+    assert_eq!(decode_val((-1i64 << 32) | 5).unwrap(), "I32(-1)");
+    assert_eq!(decode_val((-5 << 8) | 7).unwrap(), "I64(-5)");
+    assert_eq!(decode_val((3 << 32) | 3).unwrap(), "Error(Contract, #3)");
+    assert_eq!(decode_val((3 << 32) | (3 << 8) | 3).unwrap(), "Error(Storage, MissingValue)");
+    assert_eq!(decode_val((2 << 32) | 75).unwrap(), "VecObject(#2)");
+
+    // Unknown tags and malformed bodies
+    assert_eq!(decode_val(15), None);
+    assert_eq!(decode_val((12 << 8) | 3), None);
+}
+
+#[test]
+fn test_symbol_val() {
+    assert_eq!(symbol_val(Some(symbol("transfer"))).unwrap(), "transfer");
+    assert_eq!(symbol_val(Some((7 << 32) | 4)), None);
+    assert_eq!(symbol_val(None), None);
+}
+
+#[test]
+fn test_decode_arg() {
+    assert_eq!(decode_arg(1, "StorageType"), "Persistent");
+    assert_eq!(decode_arg(5, "StorageType"), "0x5");
+    assert_eq!(decode_arg(-1, "u64"), "18446744073709551615");
+    assert_eq!(decode_arg(-1, "i64"), "-1");
+    assert_eq!(decode_arg(symbol("admin"), "Val"), "Symbol(\"admin\")");
+    assert_eq!(decode_arg(15, "Val"), "0xf");
+}
+
+#[test]
+fn test_format_call_args() {
+    let wasm = parse_wat(&format!(
+        r#"
+        (module
+            (import "l" "_" (func $put (param i64 i64 i64) (result i64)))
+            (func $main (export "main") (param i64)
+                i64.const {}
+                local.get 0
+                i64.const 1
+                call $put
+                drop
+                i64.const 2
+                call $helper
+                local.get 0
+                call $put
+                drop)
+            (func $helper (param i64)
+                i64.const 2
+                i64.const 2
+                i64.const 4
+                call $put
+                drop)
+        )
+        "#,
+        symbol("admin")
+    ));
+    let mut data = parse_wasm_module(&wasm, None).unwrap();
+    let function_map = HashMap::from([host("l", "_", "put", &[("k", "Val"), ("v", "Val"), ("t", "StorageType")])]);
+    apply_env_functions(&mut data, &function_map);
+
+    let rendered: Vec<String> = data.call_graph[&1].iter().map(|edge| format_call_args(&data, edge)).collect();
+    // The value is a local; the last call's arguments were pushed by other instructions
+    assert_eq!(rendered, vec!["(k=Symbol(\"admin\"), t=Persistent)", "", ""]);
+
+    // Only imports described in env.json have their arguments decoded
+    let rendered: Vec<String> = data.call_graph[&2].iter().map(|edge| format_call_args(&data, edge)).collect();
+    assert_eq!(rendered, vec!["(k=void, v=void, t=0x4)"]);
+}
+
+#[test]
+fn test_memory_strings() {
+    // U32Val arguments are (n << 32) | 4
+    let wasm = parse_wat(
+        r#"
+        (module
+            (import "x" "_" (func $log_from_linear_memory (param i64 i64 i64 i64) (result i64)))
+            (import "m" "9" (func $map_new_from_linear_memory (param i64 i64 i64) (result i64)))
+            (import "b" "j" (func $symbol_new_from_linear_memory (param i64 i64) (result i64)))
+            (memory 1)
+            (data (i32.const 16) "transfer failed")
+            (data (i32.const 32) "\30\00\00\00\04\00\00\00\34\00\00\00\02\00\00\00")
+            (data (i32.const 48) "nameid\ff")
+            (func $main (export "main") (param i64)
+                i64.const 0x1000000004
+                i64.const 0xf00000004
+                i64.const 4
+                i64.const 4
+                call $log_from_linear_memory
+                drop
+                i64.const 0x2000000004
+                local.get 0
+                i64.const 0x200000004
+                call $map_new_from_linear_memory
+                drop
+                i64.const 0x3600000004
+                i64.const 0x100000004
+                call $symbol_new_from_linear_memory
+                drop
+                i64.const 0x1000000004
+                i64.const 0x100000004
+                call $symbol_new_from_linear_memory
+                drop)
+        )
+        "#,
+    );
+    let mut data = parse_wasm_module(&wasm, None).unwrap();
+    let u32_vals = |names: &[&'static str]| names.iter().map(|&name| (name, "U32Val")).collect::<Vec<_>>();
+    let function_map = HashMap::from([
+        host("x", "_", "log_from_linear_memory", &u32_vals(&["msg_pos", "msg_len", "vals_pos", "vals_len"])),
+        host("m", "9", "map_new_from_linear_memory", &u32_vals(&["keys_pos", "vals_pos", "len"])),
+        host("b", "j", "symbol_new_from_linear_memory", &u32_vals(&["lm_pos", "len"])),
+    ]);
+    apply_env_functions(&mut data, &function_map);
+
+    let rendered: Vec<String> = data.call_graph[&3].iter().map(|edge| format_call_args(&data, edge)).collect();
+    assert_eq!(
+        rendered,
+        vec![
+            "(msg=\"transfer failed\", vals_pos=U32(0), vals_len=U32(0))",
+            "(keys=[\"name\", \"id\"])",
+            "(lm=0xff)",
+            "(lm=\"t\")",
+        ]
+    );
+}